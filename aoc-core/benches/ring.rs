@@ -0,0 +1,67 @@
+// Compares mixing numbers like in 2022 day 20 with `Ring` against doing it
+// with `Vec::remove` and `Vec::insert`. Run with `cargo bench -p aoc-core`.
+
+use aoc_core::{
+    bench::{measure, Stats},
+    error::Result,
+    util::Ring,
+};
+
+const WARMUP: u32 = 1;
+const RUNS: u32 = 5;
+const ROUNDS: usize = 10;
+
+fn numbers(len: usize) -> Vec<i64> {
+    // A simple linear congruential generator, so that every run mixes the
+    // same numbers
+    let mut state: i64 = 12345;
+    (0..len)
+        .map(|_| {
+            state = (state * 1103515245 + 12345) % 2147483648;
+            (state % 20000 - 10000) * 811589153
+        })
+        .collect()
+}
+
+fn mix_vec(numbers: &[i64]) -> i64 {
+    let mut v: Vec<(usize, i64)> = numbers.iter().copied().enumerate().collect();
+    for _ in 0..ROUNDS {
+        for id in 0..v.len() {
+            let index = v.iter().position(|(i, _)| *i == id).unwrap();
+            let elem = v.remove(index);
+            let target = (index as i64 + elem.1).rem_euclid(v.len() as i64) as usize;
+            v.insert(target, elem);
+        }
+    }
+    v[0].1
+}
+
+fn mix_ring(numbers: &[i64]) -> i64 {
+    let mut ring = Ring::new(numbers.to_vec());
+    for _ in 0..ROUNDS {
+        for id in 0..ring.len() {
+            ring.move_by(id, *ring.value(id));
+        }
+    }
+    *ring.get(0).unwrap()
+}
+
+fn print_stats(name: &str, len: usize, stats: &Stats) {
+    println!(
+        "{name:<4} n = {len:>6}: min {:>10.2?}, median {:>10.2?}, mean {:>10.2?}, stddev {:>10.2?}",
+        stats.min, stats.median, stats.mean, stats.stddev
+    );
+}
+
+fn main() -> Result<()> {
+    for len in [1000, 5000, 10000] {
+        let numbers = numbers(len);
+        assert_eq!(mix_vec(&numbers), mix_ring(&numbers));
+
+        let vec = measure(WARMUP, RUNS, || Ok(mix_vec(&numbers)))?;
+        print_stats("vec", len, &vec);
+        let ring = measure(WARMUP, RUNS, || Ok(mix_ring(&numbers)))?;
+        print_stats("ring", len, &ring);
+    }
+    Ok(())
+}
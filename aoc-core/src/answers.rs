@@ -0,0 +1,167 @@
+use std::{collections::BTreeMap, fmt, fs, path::Path, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::{
+    error::{Error, Result},
+    solution::{DynSolution, Params, Part},
+    util::read_input,
+};
+
+pub(crate) const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{number}"),
+            Answer::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PartAnswers {
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+    // The values from the puzzle text that this input needs
+    #[serde(default)]
+    params: Params,
+}
+
+// The recorded answers for each day, keyed by the kind of input they are for,
+// e.g. "input", "sample" or "sample_2".
+#[derive(Debug, Default)]
+pub struct Answers {
+    days: BTreeMap<u8, BTreeMap<String, PartAnswers>>,
+}
+
+impl FromStr for Answers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let tables: BTreeMap<String, BTreeMap<String, PartAnswers>> = toml::from_str(s)
+            .map_err(|err| Error::new(format!("invalid answers file: {}", err.message())))?;
+
+        let days = tables
+            .into_iter()
+            .map(|(key, kinds)| {
+                key.strip_prefix("day")
+                    .and_then(|day| day.parse::<u8>().ok())
+                    .map(|day| (day, kinds))
+                    .ok_or_else(|| Error::new(format!("invalid day \"{key}\" in answers file")))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { days })
+    }
+}
+
+impl Answers {
+    pub fn load(resource_dir: &Path) -> Result<Self> {
+        let path = resource_dir.join(ANSWERS_FILE);
+        fs::read_to_string(&path)
+            .map_err(|err| Error::new(format!("could not open {}: {err}", path.display())))?
+            .parse()
+    }
+
+    pub fn get(&self, day: u8, kind: &str, part: Part) -> Option<String> {
+        let answers = self.days.get(&day)?.get(kind)?;
+        match part {
+            Part::One => answers.part1.as_ref(),
+            Part::Two => answers.part2.as_ref(),
+        }
+        .map(|answer| answer.to_string())
+    }
+
+    pub fn params(&self, day: u8, kind: &str) -> Params {
+        self.days
+            .get(&day)
+            .and_then(|kinds| kinds.get(kind))
+            .map(|answers| answers.params.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn kinds(&self, day: u8) -> impl Iterator<Item = &str> {
+        self.days
+            .get(&day)
+            .into_iter()
+            .flat_map(|kinds| kinds.keys().map(String::as_str))
+    }
+}
+
+// Returns the input file, relative to the resource directory, for the given
+// kind of input
+pub fn input_file(day: u8, kind: &str) -> String {
+    match kind.split_once('_') {
+        Some((directory, suffix)) => format!("{directory}/day{day}_{suffix}.txt"),
+        None => format!("{kind}/day{day}.txt"),
+    }
+}
+
+// Runs the part on every input that has a recorded answer for it, so that
+// each day's tests use the same answers as the `check` command. Meant to be
+// called from the tests of a year crate, which run in the crate directory.
+pub fn check_answers(solution: &dyn DynSolution, part: Part) -> Result<()> {
+    let answers = Answers::load(Path::new("res"))?;
+    let day = solution.day();
+
+    let mut checked = 0;
+    for kind in answers.kinds(day) {
+        if let Some(expected) = answers.get(day, kind, part) {
+            let input = read_input(&Path::new("res").join(input_file(day, kind)))?;
+            let params = answers.params(day, kind);
+            assert_eq!(
+                solution.parse(&input, &params)?.run(part)?,
+                expected,
+                "day {day}, part {}, {kind} input",
+                part.number()
+            );
+            checked += 1;
+        }
+    }
+    assert!(
+        checked > 0,
+        "day {day}, part {} has no recorded answers",
+        part.number()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let answers: Answers =
+            "[day3.sample]\npart1 = 157\n\n[day3.sample_2]\npart2 = \"ab\"\n".parse()?;
+        assert_eq!(answers.get(3, "sample", Part::One), Some("157".to_owned()));
+        assert_eq!(answers.get(3, "sample", Part::Two), None);
+        assert_eq!(answers.get(3, "sample_2", Part::Two), Some("ab".to_owned()));
+        assert_eq!(answers.get(4, "sample", Part::One), None);
+        assert_eq!(answers.kinds(3).collect::<Vec<_>>(), ["sample", "sample_2"]);
+
+        assert_eq!(answers.params(3, "sample"), Params::default());
+
+        let answers: Answers = "[day15.sample]\nparams = { row = 10 }\n".parse()?;
+        assert_eq!(answers.params(15, "sample").get("row", 0), Ok(10));
+        assert!("[day3.sample]\npart3 = 1\n".parse::<Answers>().is_err());
+        assert!("[three.sample]\npart1 = 1\n".parse::<Answers>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_file() {
+        assert_eq!(input_file(1, "input"), "input/day1.txt");
+        assert_eq!(input_file(6, "sample"), "sample/day6.txt");
+        assert_eq!(input_file(6, "sample_3"), "sample/day6_3.txt");
+    }
+}
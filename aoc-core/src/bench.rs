@@ -0,0 +1,165 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "Stats need at least one sample");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            sorted
+                .iter()
+                .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+
+    // Relative change of the median compared to `baseline`, e.g. 0.1 if this
+    // is 10 % slower
+    pub fn change_from(&self, baseline: &Stats) -> f64 {
+        self.median.as_secs_f64() / baseline.median.as_secs_f64() - 1.0
+    }
+}
+
+// Runs `f` `warmup` times without timing it, and then times it `runs` times.
+// Stops at the first error.
+pub fn measure<T>(warmup: u32, runs: u32, mut f: impl FnMut() -> Result<T>) -> Result<Stats> {
+    for _ in 0..warmup {
+        f()?;
+    }
+
+    let samples = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            f()?;
+            Ok(start.elapsed())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Stats::from_samples(&samples))
+}
+
+// Benchmark results from an earlier run, keyed by e.g. "2022/day16/part1"
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Baseline {
+    results: BTreeMap<String, Stats>,
+}
+
+impl Baseline {
+    // Returns an empty baseline if the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|err| Error::new(format!("invalid baseline {}: {err}", path.display())))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|err| Error::new(format!("could not serialize baseline: {err}")))?;
+        Ok(fs::write(path, content + "\n")?)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Stats> {
+        self.results.get(key)
+    }
+
+    pub fn insert(&mut self, key: String, stats: Stats) {
+        self.results.insert(key, stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(samples: &[u64]) -> Vec<Duration> {
+        samples
+            .iter()
+            .map(|ms| Duration::from_millis(*ms))
+            .collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&millis(&[4, 2, 9, 5]));
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_micros(4500));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        assert_eq!(stats.stddev.as_micros(), 2943);
+
+        let stats = Stats::from_samples(&millis(&[7]));
+        assert_eq!(stats.median, Duration::from_millis(7));
+        assert_eq!(stats.stddev, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_change_from() {
+        let baseline = Stats::from_samples(&millis(&[10]));
+        let slower = Stats::from_samples(&millis(&[12]));
+        assert!((slower.change_from(&baseline) - 0.2).abs() < 1e-9);
+        assert!((baseline.change_from(&slower) + 1.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_measure() -> Result<()> {
+        let mut calls = 0;
+        measure(2, 3, || {
+            calls += 1;
+            Ok(())
+        })?;
+        assert_eq!(calls, 5);
+
+        let mut calls = 0;
+        let result = measure(1, 3, || {
+            calls += 1;
+            if calls == 2 {
+                Err(Error::new("failed"))
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result, Err(Error::new("failed")));
+        assert_eq!(calls, 2);
+        Ok(())
+    }
+}
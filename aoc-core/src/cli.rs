@@ -0,0 +1,439 @@
+use std::{env, path::PathBuf, process::ExitCode};
+
+use clap::{error::ErrorKind, ArgGroup, Args, CommandFactory, Parser, Subcommand};
+
+use crate::{
+    config::{Config, InputPaths},
+    fetch::{Client, BASE_URL_VAR, SESSION_VAR},
+    runner,
+    solution::{DynSolution, Part, Registry},
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "aoc",
+    about = "Solutions for Advent of Code",
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    // Running without a subcommand is the same as `run`
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the selected days and print their answers
+    Run(RunArgs),
+    /// List all available days
+    List {
+        /// Only list the days of this year
+        #[arg(short, long)]
+        year: Option<u16>,
+    },
+    /// Time the selected days over several runs
+    Bench(BenchArgs),
+    /// Compare the answers of the selected days against the recorded ones
+    Check(Selection),
+    /// Download the inputs of the selected days that aren't there yet
+    Fetch(FetchArgs),
+    /// Solve a part on the real input and submit the answer
+    Submit(SubmitArgs),
+}
+
+#[derive(Debug, Default, Args)]
+pub struct Selection {
+    /// Year to select the days from [default: the latest year]
+    #[arg(short, long)]
+    pub year: Option<u16>,
+
+    /// Days to select, e.g. `1-5,12` [default: all days]
+    #[arg(short, long = "days", visible_alias = "day", value_parser = parse_days)]
+    pub days: Option<Days>,
+
+    /// Only select one of the parts
+    #[arg(short, long, value_parser = parse_part)]
+    pub part: Option<Part>,
+
+    /// Use the sample input instead of the real input
+    #[arg(short, long, conflicts_with = "input")]
+    pub sample: bool,
+
+    /// Read the input from this file instead, or from stdin if it is `-`.
+    /// Requires a single day
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("replay").args(["visualize", "render"])))]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub selection: Selection,
+
+    /// Replay the simulations of the selected days in the terminal
+    #[arg(long)]
+    pub visualize: bool,
+
+    /// Render the simulation to an animated GIF, or to numbered PNG files.
+    /// Requires a single day and part
+    #[arg(long, value_name = "PATH", requires = "part")]
+    pub render: Option<PathBuf>,
+
+    /// Frames per second of the replay
+    #[arg(long, default_value_t = 30.0, value_parser = parse_fps, requires = "replay")]
+    pub fps: f64,
+
+    /// Only show every Nth frame of the replay
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..),
+        requires = "replay"
+    )]
+    pub skip: u32,
+
+    /// Width and height of a cell in the rendered images, in pixels
+    #[arg(
+        long,
+        value_name = "PIXELS",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u32).range(1..=64),
+        requires = "render"
+    )]
+    pub scale: u32,
+}
+
+#[derive(Debug, Args)]
+pub struct FetchArgs {
+    /// Year to fetch the days from [default: the latest year]
+    #[arg(short, long)]
+    pub year: Option<u16>,
+
+    /// Days to fetch, e.g. `1-5,12` [default: all days]
+    #[arg(short, long = "days", visible_alias = "day", value_parser = parse_days)]
+    pub days: Option<Days>,
+
+    /// Also extract the sample input from each puzzle's page
+    #[arg(short, long)]
+    pub sample: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SubmitArgs {
+    /// Year of the day [default: the latest year]
+    #[arg(short, long)]
+    pub year: Option<u16>,
+
+    /// Day to submit the answer of
+    #[arg(short, long, value_parser = parse_day)]
+    pub day: u8,
+
+    /// Part to submit the answer of
+    #[arg(short, long, value_parser = parse_part)]
+    pub part: Part,
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub selection: Selection,
+
+    /// Number of timed runs of each part
+    #[arg(
+        short = 'n',
+        long,
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub runs: u32,
+
+    /// Number of untimed runs of each part before the timed ones
+    #[arg(short, long, default_value_t = 3)]
+    pub warmup: u32,
+
+    /// Results to compare against
+    #[arg(
+        short,
+        long,
+        value_name = "PATH",
+        default_value = "bench_baseline.json"
+    )]
+    pub baseline: PathBuf,
+
+    /// Save the results as the new baseline
+    #[arg(long)]
+    pub save: bool,
+
+    /// Flag parts whose median is this many percent slower than the baseline
+    #[arg(short, long, value_name = "PERCENT", default_value_t = 10.0)]
+    pub threshold: f64,
+}
+
+impl Selection {
+    pub fn parts(&self) -> Vec<Part> {
+        Part::ALL
+            .into_iter()
+            .filter(|part| self.part.is_none_or(|selected| selected == *part))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Days(pub Vec<u8>);
+
+fn parse_day(s: &str) -> Result<u8, String> {
+    s.trim()
+        .parse()
+        .ok()
+        .filter(|day| (1..=25).contains(day))
+        .ok_or_else(|| format!("invalid day \"{s}\", must be a number between 1 and 25"))
+}
+
+fn parse_days(s: &str) -> Result<Days, String> {
+    let mut days = Vec::new();
+    for range in s.split(',') {
+        match range.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (parse_day(first)?, parse_day(last)?);
+                if first > last {
+                    return Err(format!("invalid day range \"{range}\""));
+                }
+                days.extend(first..=last);
+            }
+            None => days.push(parse_day(range)?),
+        }
+    }
+    days.sort_unstable();
+    days.dedup();
+    Ok(Days(days))
+}
+
+fn parse_part(s: &str) -> Result<Part, String> {
+    match s {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("invalid part \"{s}\", must be either 1 or 2")),
+    }
+}
+
+fn parse_fps(s: &str) -> Result<f64, String> {
+    s.parse()
+        .ok()
+        .filter(|fps: &f64| fps.is_finite() && *fps > 0.0)
+        .ok_or_else(|| format!("invalid frame rate \"{s}\", must be a positive number"))
+}
+
+fn exit_with_error(kind: ErrorKind, message: &str) -> ! {
+    Cli::command().error(kind, message).exit()
+}
+
+fn select<'a>(
+    registry: &'a Registry,
+    config: &Config,
+    selection: &Selection,
+) -> (InputPaths, Vec<&'a dyn DynSolution>) {
+    let Some(year) = selection.year.or_else(|| registry.years().last()) else {
+        exit_with_error(ErrorKind::InvalidValue, "there are no years to select from");
+    };
+    let Some(resource_dir) = registry.resource_dir(year) else {
+        exit_with_error(ErrorKind::InvalidValue, &format!("{year} has no solutions"));
+    };
+
+    let solutions: Vec<_> = match &selection.days {
+        Some(Days(days)) => days
+            .iter()
+            .filter_map(|day| registry.get(year, *day))
+            .collect(),
+        None => registry.iter().filter(|s| s.year() == year).collect(),
+    };
+
+    if solutions.is_empty() {
+        exit_with_error(
+            ErrorKind::InvalidValue,
+            "none of the selected days are solved",
+        );
+    }
+    if selection.input.is_some() && solutions.len() != 1 {
+        exit_with_error(
+            ErrorKind::ArgumentConflict,
+            "--input can only be used when a single day is selected",
+        );
+    }
+    (InputPaths::new(year, resource_dir, config), solutions)
+}
+
+// Parses the command line and runs the command with the given solutions.
+// Invalid arguments exit with code 2, and failing days with code 1.
+pub fn main(registry: &Registry) -> ExitCode {
+    let cli = Cli::parse();
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let success = match cli.command.unwrap_or(Command::Run(cli.run)) {
+        Command::Run(args) => {
+            let (paths, solutions) = select(registry, &config, &args.selection);
+            if args.render.is_some() && solutions.len() != 1 {
+                exit_with_error(
+                    ErrorKind::ArgumentConflict,
+                    "--render can only be used when a single day is selected",
+                );
+            }
+            runner::run(&solutions, &paths, &args)
+        }
+        Command::List { year } => {
+            let solutions: Vec<_> = registry
+                .iter()
+                .filter(|s| year.is_none_or(|year| s.year() == year))
+                .collect();
+            runner::list(&solutions);
+            true
+        }
+        Command::Bench(args) => {
+            let (paths, solutions) = select(registry, &config, &args.selection);
+            runner::bench(&solutions, &paths, &args)
+        }
+        Command::Check(selection) => {
+            let (paths, solutions) = select(registry, &config, &selection);
+            runner::check(&solutions, &paths, &selection)
+        }
+        Command::Fetch(args) => {
+            let selection = Selection {
+                year: args.year,
+                days: args.days.clone(),
+                ..Selection::default()
+            };
+            let (paths, solutions) = select(registry, &config, &selection);
+            let client = Client::new(
+                &config.base_url(env::var(BASE_URL_VAR).ok()),
+                env::var(SESSION_VAR).ok(),
+            );
+            runner::fetch(&solutions, &paths, &client, &args)
+        }
+        Command::Submit(args) => {
+            let selection = Selection {
+                year: args.year,
+                days: Some(Days(vec![args.day])),
+                part: Some(args.part),
+                ..Selection::default()
+            };
+            let (paths, solutions) = select(registry, &config, &selection);
+            let client = Client::new(
+                &config.base_url(env::var(BASE_URL_VAR).ok()),
+                env::var(SESSION_VAR).ok(),
+            );
+            runner::submit(solutions[0], &paths, &client, args.part)
+        }
+    };
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("3"), Ok(Days(vec![3])));
+        assert_eq!(parse_days("1-5,12"), Ok(Days(vec![1, 2, 3, 4, 5, 12])));
+        assert_eq!(parse_days("12,2-3,3"), Ok(Days(vec![2, 3, 12])));
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("5-3").is_err());
+        assert!(parse_days("1,,2").is_err());
+        assert!(parse_days("a-b").is_err());
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let cli = Cli::try_parse_from(["aoc", "check", "-d", "1-2", "-p", "2"]).unwrap();
+        match cli.command {
+            Some(Command::Check(selection)) => {
+                assert_eq!(selection.days, Some(Days(vec![1, 2])));
+                assert_eq!(selection.parts(), vec![Part::Two]);
+            }
+            _ => panic!("expected the check command"),
+        }
+
+        let cli = Cli::try_parse_from(["aoc", "-s", "-y", "2022"]).unwrap();
+        assert!(cli.command.is_none());
+        assert!(cli.run.selection.sample);
+        assert_eq!(cli.run.selection.year, Some(2022));
+        assert_eq!(cli.run.selection.parts(), Part::ALL);
+        assert!(!cli.run.visualize);
+
+        assert!(Cli::try_parse_from(["aoc", "-p", "3"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--unknown"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "-s", "-i", "input.txt"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "bench", "-n", "0"]).is_err());
+
+        let cli = Cli::try_parse_from(["aoc", "fetch", "-d", "3", "-s"]).unwrap();
+        match cli.command {
+            Some(Command::Fetch(args)) => {
+                assert_eq!(args.days, Some(Days(vec![3])));
+                assert!(args.sample);
+            }
+            _ => panic!("expected the fetch command"),
+        }
+        assert!(Cli::try_parse_from(["aoc", "fetch", "-p", "1"]).is_err());
+
+        let cli = Cli::try_parse_from(["aoc", "submit", "--day", "7", "--part", "2"]).unwrap();
+        match cli.command {
+            Some(Command::Submit(args)) => {
+                assert_eq!(args.day, 7);
+                assert_eq!(args.part, Part::Two);
+            }
+            _ => panic!("expected the submit command"),
+        }
+        assert!(Cli::try_parse_from(["aoc", "submit", "-d", "7"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "submit", "-d", "1-2", "-p", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_visualize_args() {
+        let cli = Cli::try_parse_from(["aoc", "run", "-d", "23", "--visualize", "--fps", "12.5"])
+            .unwrap();
+        match cli.command {
+            Some(Command::Run(args)) => {
+                assert!(args.visualize);
+                assert_eq!(args.fps, 12.5);
+                assert_eq!(args.skip, 1);
+            }
+            _ => panic!("expected the run command"),
+        }
+
+        let cli = Cli::try_parse_from(["aoc", "--visualize", "--skip", "10"]).unwrap();
+        assert_eq!(cli.run.skip, 10);
+
+        assert!(Cli::try_parse_from(["aoc", "--fps", "10"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--visualize", "--fps", "0"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--visualize", "--skip", "0"]).is_err());
+
+        let cli =
+            Cli::try_parse_from(["aoc", "-d", "14", "-p", "2", "--render", "sand.gif"]).unwrap();
+        assert_eq!(cli.run.render, Some(PathBuf::from("sand.gif")));
+        assert_eq!(cli.run.scale, 4);
+        assert!(
+            Cli::try_parse_from(["aoc", "--render", "out.gif", "--skip", "5", "-p", "1"]).is_ok()
+        );
+        assert!(Cli::try_parse_from(["aoc", "--render", "out.gif"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--visualize", "--scale", "2"]).is_err());
+        assert!(
+            Cli::try_parse_from(["aoc", "--visualize", "--render", "out.gif", "-p", "1"]).is_err()
+        );
+    }
+}
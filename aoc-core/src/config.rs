@@ -0,0 +1,203 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    answers::input_file,
+    error::{Error, Result},
+    fetch::DEFAULT_BASE_URL,
+    submit::HISTORY_FILE,
+};
+
+// Points to a directory with the real inputs of each year, laid out as
+// `<year>/day<N>.txt`. Takes precedence over the config file.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+// Looked up in the current directory, and then next to the executable
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    // Same as `AOC_INPUT_DIR`. Relative paths are relative to the config file.
+    pub input_dir: Option<PathBuf>,
+    // The site that inputs are fetched from, same as `AOC_BASE_URL`
+    pub base_url: Option<String>,
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_owned))
+}
+
+impl Config {
+    pub fn parse(s: &str, base_dir: &Path) -> Result<Self> {
+        let mut config: Config = toml::from_str(s)
+            .map_err(|err| Error::new(format!("invalid config file: {}", err.message())))?;
+        config.input_dir = config.input_dir.map(|dir| base_dir.join(dir));
+        Ok(config)
+    }
+
+    // Returns the default config if there is no config file
+    pub fn load() -> Result<Self> {
+        let candidates = [env::current_dir().ok(), exe_dir()];
+        let Some(path) = candidates
+            .into_iter()
+            .flatten()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|path| path.is_file())
+        else {
+            return Ok(Self::default());
+        };
+
+        let content = fs::read_to_string(&path)
+            .map_err(|err| Error::new(format!("could not open {}: {err}", path.display())))?;
+        Self::parse(&content, path.parent().unwrap_or(Path::new(".")))
+            .map_err(|err| Error::new(format!("{}: {}", path.display(), err.reason)))
+    }
+
+    // The directory with the real inputs of all years, if one is configured
+    pub fn input_root(&self, env_value: Option<OsString>) -> Option<PathBuf> {
+        env_value
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| self.input_dir.clone())
+    }
+
+    pub fn base_url(&self, env_value: Option<String>) -> String {
+        env_value
+            .filter(|value| !value.is_empty())
+            .or_else(|| self.base_url.clone())
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_owned())
+    }
+}
+
+// Where the inputs of a year are read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputPaths {
+    resource_dir: PathBuf,
+    input_dir: Option<PathBuf>,
+}
+
+impl InputPaths {
+    // The year crates register their resource directory at compile time. If
+    // the binary has been moved to another machine, `res/<year>` next to the
+    // executable is used instead.
+    pub fn new(year: u16, resource_dir: &Path, config: &Config) -> Self {
+        let resource_dir = match exe_dir() {
+            Some(dir) if !resource_dir.is_dir() => dir.join("res").join(year.to_string()),
+            _ => resource_dir.to_owned(),
+        };
+        Self {
+            resource_dir,
+            input_dir: config
+                .input_root(env::var_os(INPUT_DIR_VAR))
+                .map(|root| root.join(year.to_string())),
+        }
+    }
+
+    // The answers and the sample inputs always come from here
+    pub fn resource_dir(&self) -> &Path {
+        &self.resource_dir
+    }
+
+    pub fn input_file(&self, day: u8, kind: &str) -> PathBuf {
+        match &self.input_dir {
+            Some(dir) if kind == "input" => dir.join(format!("day{day}.txt")),
+            _ => self.resource_dir.join(input_file(day, kind)),
+        }
+    }
+
+    // Where the submitted answers of the year are recorded
+    pub fn history_file(&self) -> PathBuf {
+        match &self.input_dir {
+            Some(dir) => dir.join(HISTORY_FILE),
+            None => self.resource_dir.join("input").join(HISTORY_FILE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse("input_dir = \"inputs\"\n", Path::new("/home/elf")).unwrap();
+        assert_eq!(config.input_dir, Some(PathBuf::from("/home/elf/inputs")));
+
+        let config = Config::parse("input_dir = \"/inputs\"\n", Path::new("/home/elf")).unwrap();
+        assert_eq!(config.input_dir, Some(PathBuf::from("/inputs")));
+
+        assert_eq!(Config::parse("", Path::new(".")), Ok(Config::default()));
+        assert!(Config::parse("input = \"inputs\"\n", Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn test_input_root() {
+        let config = Config {
+            input_dir: Some(PathBuf::from("from_config")),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.input_root(Some("from_env".into())),
+            Some(PathBuf::from("from_env"))
+        );
+        assert_eq!(
+            config.input_root(Some("".into())),
+            Some(PathBuf::from("from_config"))
+        );
+        assert_eq!(Config::default().input_root(None), None);
+    }
+
+    #[test]
+    fn test_base_url() {
+        let config =
+            Config::parse("base_url = \"http://localhost:8080\"\n", Path::new(".")).unwrap();
+        assert_eq!(config.base_url(None), "http://localhost:8080");
+        assert_eq!(
+            config.base_url(Some("http://mock".to_owned())),
+            "http://mock"
+        );
+        assert_eq!(Config::default().base_url(None), DEFAULT_BASE_URL);
+    }
+
+    #[test]
+    fn test_input_file() {
+        let paths = InputPaths {
+            resource_dir: PathBuf::from("res"),
+            input_dir: Some(PathBuf::from("inputs/2022")),
+        };
+        assert_eq!(
+            paths.input_file(3, "input"),
+            PathBuf::from("inputs/2022/day3.txt")
+        );
+        assert_eq!(
+            paths.input_file(3, "sample_2"),
+            PathBuf::from("res/sample/day3_2.txt")
+        );
+        assert_eq!(
+            paths.history_file(),
+            PathBuf::from("inputs/2022/submissions.json")
+        );
+
+        let paths = InputPaths {
+            input_dir: None,
+            ..paths
+        };
+        assert_eq!(
+            paths.input_file(3, "input"),
+            PathBuf::from("res/input/day3.txt")
+        );
+        assert_eq!(
+            paths.history_file(),
+            PathBuf::from("res/input/submissions.json")
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::{fmt, io};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub day: Option<u8>,
+    pub part: Option<u8>,
+    // 1-indexed line in the input file that caused the error, if any
+    pub line: Option<usize>,
+    // 1-indexed character in that line, if known
+    pub column: Option<usize>,
+    pub reason: String,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            day: None,
+            part: None,
+            line: None,
+            column: None,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn at_line(line: usize, reason: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            ..Self::new(reason)
+        }
+    }
+
+    pub fn at(line: usize, column: usize, reason: impl Into<String>) -> Self {
+        Self {
+            column: Some(column),
+            ..Self::at_line(line, reason)
+        }
+    }
+
+    // Used to add context to errors further up the call chain. Context that
+    // has already been set is kept.
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    pub fn with_day(mut self, day: u8) -> Self {
+        self.day.get_or_insert(day);
+        self
+    }
+
+    pub fn with_part(mut self, part: u8) -> Self {
+        self.part.get_or_insert(part);
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let context = [
+            self.day.map(|day| format!("day {day}")),
+            self.part.map(|part| format!("part {part}")),
+            self.line.map(|line| format!("line {line}")),
+            self.column.map(|column| format!("column {column}")),
+        ];
+        let context = context.into_iter().flatten().collect::<Vec<_>>();
+
+        if context.is_empty() {
+            write!(f, "{}", self.reason)
+        } else {
+            write!(f, "{}: {}", context.join(", "), self.reason)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::new(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Error::new("no solution").to_string(), "no solution");
+        assert_eq!(
+            Error::at_line(3, "invalid number")
+                .with_day(4)
+                .with_part(2)
+                .to_string(),
+            "day 4, part 2, line 3: invalid number"
+        );
+        assert_eq!(
+            Error::at(2, 7, "expected a number").to_string(),
+            "line 2, column 7: expected a number"
+        );
+    }
+
+    #[test]
+    fn test_context_is_kept() {
+        let err = Error::new("bad input").with_part(1).with_part(2);
+        assert_eq!(err.part, Some(1));
+    }
+}
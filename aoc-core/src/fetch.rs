@@ -0,0 +1,288 @@
+use std::{
+    cell::Cell,
+    fs,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::error::{Error, Result};
+
+// The session cookie of a logged in Advent of Code account
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+// Overrides the site to fetch from, e.g. to test against a local server.
+// Takes precedence over the config file.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+// Advent of Code asks automated tools to throttle their requests
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(3);
+
+const USER_AGENT: &str = concat!(
+    "aoc-runner/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://www.reddit.com/r/adventofcode/wiki/faqs/automation)"
+);
+
+// A client for the Advent of Code site, or any server with the same URLs
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: Option<String>,
+    min_interval: Duration,
+    last_request: Cell<Option<Instant>>,
+}
+
+impl Client {
+    // The session is only needed once something is actually requested
+    pub fn new(base_url: &str, session: Option<String>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .redirects(0)
+                .build(),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.filter(|session| !session.is_empty()),
+            min_interval: MIN_REQUEST_INTERVAL,
+            last_request: Cell::new(None),
+        }
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    fn session(&self) -> Result<&str> {
+        self.session.as_deref().ok_or_else(|| {
+            Error::new(format!(
+                "set {SESSION_VAR} to the session cookie of your Advent of Code account"
+            ))
+        })
+    }
+
+    // Waits until the last request is long enough ago
+    fn throttle(&self) {
+        if let Some(last) = self.last_request.get() {
+            thread::sleep(self.min_interval.saturating_sub(last.elapsed()));
+        }
+        self.last_request.set(Some(Instant::now()));
+    }
+
+    pub(crate) fn request(&self, method: &str, path: &str) -> Result<ureq::Request> {
+        let session = self.session()?;
+        self.throttle();
+        Ok(self
+            .agent
+            .request(method, &format!("{}{path}", self.base_url))
+            .set("Cookie", &format!("session={session}")))
+    }
+
+    // Sends the request, with `form` as its body if there is one
+    pub(crate) fn send(
+        &self,
+        request: ureq::Request,
+        form: Option<&[(&str, &str)]>,
+    ) -> Result<String> {
+        let url = request.url().to_owned();
+        let result = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+        let response = match result {
+            Ok(response) if response.status() == 200 => response,
+            Ok(response) | Err(ureq::Error::Status(_, response)) => {
+                return Err(status_error(&url, &response))
+            }
+            Err(err) => return Err(Error::new(format!("could not reach {url}: {err}"))),
+        };
+        response
+            .into_string()
+            .map_err(|err| Error::new(format!("could not read the response of {url}: {err}")))
+    }
+
+    fn get(&self, path: &str) -> Result<String> {
+        let request = self.request("GET", path)?;
+        self.send(request, None)
+    }
+
+    pub fn input(&self, year: u16, day: u8) -> Result<String> {
+        self.get(&format!("/{year}/day/{day}/input"))
+    }
+
+    pub fn puzzle_page(&self, year: u16, day: u8) -> Result<String> {
+        self.get(&format!("/{year}/day/{day}"))
+    }
+}
+
+fn status_error(url: &str, response: &ureq::Response) -> Error {
+    let reason = match response.status() {
+        // The site redirects to the login page or refuses the input if the
+        // session is not valid
+        300..=399 | 400 | 401 | 403 => {
+            "the session cookie is not valid, it may have expired".to_owned()
+        }
+        404 => "the puzzle is not available yet".to_owned(),
+        429 => match response.header("Retry-After") {
+            Some(seconds) => format!("too many requests, try again in {seconds} seconds"),
+            None => "too many requests, try again later".to_owned(),
+        },
+        status => format!("the server responded with status {status}"),
+    };
+    Error::new(format!("{url}: {reason}"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    Downloaded,
+    // The file already existed, so nothing was requested
+    Cached,
+}
+
+// Writes the result of `download` to `path`, unless the file already exists
+pub fn fetch_to(path: &Path, download: impl FnOnce() -> Result<String>) -> Result<Fetched> {
+    if path.exists() {
+        return Ok(Fetched::Cached);
+    }
+
+    let content = download()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|err| Error::new(format!("could not create {}: {err}", dir.display())))?;
+    }
+    fs::write(path, content)
+        .map_err(|err| Error::new(format!("could not write {}: {err}", path.display())))?;
+    Ok(Fetched::Downloaded)
+}
+
+fn unescape_html(s: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+// Returns the first code block after the puzzle text mentions an example,
+// which is the sample input on almost every day. Falls back to the first code
+// block on the page.
+pub fn extract_sample(html: &str) -> Option<String> {
+    const START: &str = "<pre><code>";
+    const END: &str = "</code></pre>";
+
+    // ASCII lowercasing keeps the byte offsets, so they can be used on `html`
+    let lowercase = html.to_ascii_lowercase();
+    let start = lowercase
+        .find("example")
+        .and_then(|i| lowercase[i..].find(START).map(|start| i + start))
+        .or_else(|| lowercase.find(START))?
+        + START.len();
+    let end = start + lowercase[start..].find(END)?;
+
+    let mut sample = unescape_html(&html[start..end]);
+    if !sample.ends_with('\n') {
+        sample.push('\n');
+    }
+    Some(sample)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{mock_server, temp_dir};
+
+    #[test]
+    fn test_fetch_input() {
+        let (url, requests) = mock_server(vec![(200, "", "1\n2\n3\n")]);
+        let client = Client::new(&url, Some("abc".to_owned()));
+        let dir = temp_dir("fetch");
+        let path = dir.join("2022").join("day1.txt");
+
+        let fetched = fetch_to(&path, || client.input(2022, 1));
+        assert_eq!(fetched, Ok(Fetched::Downloaded));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "GET /2022/day/1/input HTTP/1.1");
+        assert_eq!(request.cookie.as_deref(), Some("session=abc"));
+        assert!(request.body.is_empty());
+
+        // The server only answers once, so this must come from the cache
+        let fetched = fetch_to(&path, || client.input(2022, 1));
+        assert_eq!(fetched, Ok(Fetched::Cached));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_errors() {
+        let (url, _requests) = mock_server(vec![
+            (404, "", "Not found"),
+            (429, "Retry-After: 60\r\n", ""),
+            (302, "Location: /\r\n", ""),
+        ]);
+        let client = Client::new(&url, Some("abc".to_owned())).with_min_interval(Duration::ZERO);
+
+        let err = client.input(2022, 25).unwrap_err();
+        assert!(err.reason.ends_with("the puzzle is not available yet"));
+        let err = client.input(2022, 25).unwrap_err();
+        assert!(err.reason.ends_with("try again in 60 seconds"));
+        let err = client.puzzle_page(2022, 25).unwrap_err();
+        assert!(err.reason.contains("session cookie is not valid"));
+
+        let client = Client::new(&url, None);
+        assert!(client
+            .input(2022, 1)
+            .unwrap_err()
+            .reason
+            .contains(SESSION_VAR));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let (url, _requests) = mock_server(vec![(200, "", "a"), (200, "", "b")]);
+        let client =
+            Client::new(&url, Some("abc".to_owned())).with_min_interval(Duration::from_millis(200));
+
+        let start = Instant::now();
+        assert_eq!(client.input(2022, 1), Ok("a".to_owned()));
+        assert_eq!(client.input(2022, 2), Ok("b".to_owned()));
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_extract_sample() {
+        let html = "<article><p>Some code: <code>x</code></p><pre><code>not this</code></pre>\
+            <p>For example, suppose:</p>\n<pre><code>$ cd /\n<em>a</em> &lt; b &amp;&amp; c\n</code></pre>\
+            <pre><code>second</code></pre></article>";
+        assert_eq!(
+            extract_sample(html),
+            Some("$ cd /\na < b && c\n".to_owned())
+        );
+
+        assert_eq!(
+            extract_sample("<pre><code>1\n2</code></pre>"),
+            Some("1\n2\n".to_owned())
+        );
+        assert_eq!(extract_sample("<p>No code here</p>"), None);
+
+        // Lowercasing "İ" takes more bytes, which must not shift the offsets
+        assert_eq!(
+            extract_sample("<p>İİİ EXAMPLE:</p><PRE><CODE>ab</CODE></PRE>"),
+            Some("ab\n".to_owned())
+        );
+    }
+}
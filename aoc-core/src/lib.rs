@@ -0,0 +1,13 @@
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod config;
+pub mod error;
+pub mod fetch;
+pub mod runner;
+pub mod solution;
+pub mod submit;
+#[cfg(test)]
+mod test_util;
+pub mod util;
+pub mod visualize;
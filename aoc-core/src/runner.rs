@@ -0,0 +1,451 @@
+use std::{
+    io::{self, IsTerminal},
+    path::Path,
+    time::Instant,
+};
+
+use crate::{
+    answers::{Answers, ANSWERS_FILE},
+    bench::{measure, Baseline, Stats},
+    cli::{BenchArgs, FetchArgs, RunArgs, Selection},
+    config::InputPaths,
+    error::{Error, Result},
+    fetch::{extract_sample, fetch_to, Client, Fetched},
+    solution::{DynSolution, Params, Parsed, Part},
+    submit::{History, Verdict},
+    util::read_input,
+    visualize::{Recorder, Terminal},
+};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+
+fn colored(text: &str, color: &str) -> String {
+    if io::stdout().is_terminal() {
+        format!("{color}{text}\x1b[0m")
+    } else {
+        text.to_owned()
+    }
+}
+
+fn default_kind(selection: &Selection) -> &'static str {
+    if selection.sample {
+        "sample"
+    } else {
+        "input"
+    }
+}
+
+fn load_input(
+    solution: &dyn DynSolution,
+    paths: &InputPaths,
+    selection: &Selection,
+    kind: &str,
+) -> Result<String> {
+    match &selection.input {
+        Some(path) => read_input(path),
+        None => read_input(&paths.input_file(solution.day(), kind)),
+    }
+    .map_err(|err| err.with_day(solution.day()))
+}
+
+// The parameters of a sample from the answers file. The real input uses the
+// defaults of the solution.
+fn load_params(solution: &dyn DynSolution, paths: &InputPaths, kind: &str) -> Result<Params> {
+    if kind == "input" || !paths.resource_dir().join(ANSWERS_FILE).exists() {
+        return Ok(Params::default());
+    }
+    Answers::load(paths.resource_dir()).map(|answers| answers.params(solution.day(), kind))
+}
+
+// Solves the part while rendering its frames to the file given by `--render`
+fn render(
+    solution: &dyn DynSolution,
+    input: &dyn Parsed,
+    part: Part,
+    path: &Path,
+    args: &RunArgs,
+) -> Result<String> {
+    let mut recorder = Recorder::new(
+        path,
+        solution.palette(),
+        args.fps,
+        args.skip as usize,
+        args.scale as usize,
+    )
+    .map_err(|err| err.with_day(solution.day()))?;
+
+    let answer = input.visualize(part, &mut recorder).unwrap_or_else(|| {
+        Err(Error::new("there is nothing to render").with_day(solution.day()))
+    })?;
+    let num_frames = recorder
+        .finish()
+        .map_err(|err| err.with_day(solution.day()).with_part(part.number()))?;
+    println!("Rendered {num_frames} frames to {}", path.display());
+    Ok(answer)
+}
+
+// The commands below return false if any of the selected days failed, but
+// keep going with the remaining days.
+
+pub fn run(solutions: &[&dyn DynSolution], paths: &InputPaths, args: &RunArgs) -> bool {
+    let selection = &args.selection;
+    let total_time = Instant::now();
+    let mut success = true;
+    let mut terminal = args
+        .visualize
+        .then(|| Terminal::new(args.fps, args.skip as usize));
+
+    for solution in solutions {
+        let text = match load_input(*solution, paths, selection, default_kind(selection)) {
+            Ok(text) => text,
+            Err(err) => {
+                eprintln!("error: {err}");
+                success = false;
+                continue;
+            }
+        };
+        let params = match load_params(*solution, paths, default_kind(selection)) {
+            Ok(params) => params,
+            Err(err) => {
+                eprintln!("error: {err}");
+                success = false;
+                continue;
+            }
+        };
+        let parse_time = Instant::now();
+        let input = match solution.parse(&text, &params) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("error: {err}");
+                success = false;
+                continue;
+            }
+        };
+        println!(
+            "Day {:>2}: parsed the input in {:?}",
+            solution.day(),
+            parse_time.elapsed()
+        );
+
+        for part in selection.parts() {
+            let part_time = Instant::now();
+            let result = match (&args.render, &mut terminal) {
+                (Some(path), _) => render(*solution, input.as_ref(), part, path, args),
+                (None, Some(terminal)) => input.visualize(part, terminal).unwrap_or_else(|| {
+                    eprintln!("Day {} has nothing to visualize", solution.day());
+                    input.run(part)
+                }),
+                (None, None) => input.run(part),
+            };
+            match result {
+                Ok(answer) => println!(
+                    "Day {:>2}: part {}: {:>16}, in {:?}",
+                    solution.day(),
+                    part.number(),
+                    answer,
+                    part_time.elapsed()
+                ),
+                Err(err) => {
+                    eprintln!("error: {err}");
+                    success = false;
+                }
+            }
+        }
+    }
+
+    if solutions.len() > 1 {
+        println!("Total time elapsed: {:?}", total_time.elapsed());
+    }
+    success
+}
+
+fn format_stats(stats: &Stats) -> String {
+    format!(
+        "min {:>10.2?}, median {:>10.2?}, mean {:>10.2?}, stddev {:>10.2?}",
+        stats.min, stats.median, stats.mean, stats.stddev
+    )
+}
+
+fn format_change(stats: &Stats, baseline: Option<&Stats>, threshold: f64) -> String {
+    let Some(baseline) = baseline else {
+        return String::new();
+    };
+    let change = stats.change_from(baseline) * 100.0;
+    let text = format!("{change:+.1}%");
+    if change > threshold {
+        format!(", {} {}", colored(&text, RED), colored("regression", RED))
+    } else if change < -threshold {
+        format!(", {}", colored(&text, GREEN))
+    } else {
+        format!(", {text}")
+    }
+}
+
+pub fn bench(solutions: &[&dyn DynSolution], paths: &InputPaths, args: &BenchArgs) -> bool {
+    let selection = &args.selection;
+    let mut baseline = match Baseline::load(&args.baseline) {
+        Ok(baseline) => baseline,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return false;
+        }
+    };
+
+    let mut success = true;
+    let mut regressions = 0;
+    let mut results = Vec::new();
+
+    for solution in solutions {
+        let load = || load_input(*solution, paths, selection, default_kind(selection));
+        let mut timings = vec![("load".to_owned(), measure(args.warmup, args.runs, load))];
+
+        let params = load_params(*solution, paths, default_kind(selection));
+        if let Ok(text) = load() {
+            let parse = || solution.parse(&text, params.as_ref().map_err(Clone::clone)?);
+            timings.push(("parse".to_owned(), measure(args.warmup, args.runs, parse)));
+            if let Ok(input) = parse() {
+                for part in selection.parts() {
+                    timings.push((
+                        format!("part{}", part.number()),
+                        measure(args.warmup, args.runs, || input.run(part)),
+                    ));
+                }
+            }
+        }
+
+        for (name, timing) in timings {
+            let key = format!("{}/day{}/{name}", solution.year(), solution.day());
+            match timing {
+                Ok(stats) => {
+                    let previous = baseline.get(&key);
+                    if previous.is_some_and(|previous| {
+                        stats.change_from(previous) * 100.0 > args.threshold
+                    }) {
+                        regressions += 1;
+                    }
+                    println!(
+                        "Day {:>2}: {name:<5}: {}{}",
+                        solution.day(),
+                        format_stats(&stats),
+                        format_change(&stats, previous, args.threshold)
+                    );
+                    results.push((key, stats));
+                }
+                Err(err) => {
+                    eprintln!("error: {err}");
+                    success = false;
+                }
+            }
+        }
+    }
+
+    if regressions > 0 {
+        println!(
+            "{}",
+            colored(
+                &format!(
+                    "{regressions} regressions compared to {}",
+                    args.baseline.display()
+                ),
+                RED
+            )
+        );
+    }
+
+    if args.save {
+        for (key, stats) in results {
+            baseline.insert(key, stats);
+        }
+        if let Err(err) = baseline.save(&args.baseline) {
+            eprintln!("error: {err}");
+            return false;
+        }
+        println!("Saved the results to {}", args.baseline.display());
+    }
+    success
+}
+
+// Compares the answers against the year's answers file. With `--sample` every
+// recorded sample is checked, and an `--input` file is checked against the
+// answers for the real input.
+pub fn check(solutions: &[&dyn DynSolution], paths: &InputPaths, selection: &Selection) -> bool {
+    let answers = match Answers::load(paths.resource_dir()) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return false;
+        }
+    };
+
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut missing = 0;
+
+    for solution in solutions {
+        let day = solution.day();
+        let mut kinds: Vec<&str> = answers
+            .kinds(day)
+            .filter(|kind| kind.starts_with(default_kind(selection)))
+            .collect();
+        if kinds.is_empty() || selection.input.is_some() {
+            kinds = vec![default_kind(selection)];
+        }
+
+        for kind in kinds {
+            let text = load_input(*solution, paths, selection, kind);
+            let input = text
+                .as_deref()
+                .map_err(Clone::clone)
+                .and_then(|text| solution.parse(text, &answers.params(day, kind)));
+            for part in selection.parts() {
+                let result = input
+                    .as_ref()
+                    .map_err(Clone::clone)
+                    .and_then(|input| input.run(part));
+                let status = match (result, answers.get(day, kind, part)) {
+                    (Ok(answer), Some(expected)) if answer == expected => {
+                        passed += 1;
+                        colored("pass", GREEN)
+                    }
+                    (Ok(answer), Some(expected)) => {
+                        failed += 1;
+                        format!(
+                            "{}, expected {expected}, got {answer}",
+                            colored("fail", RED)
+                        )
+                    }
+                    (Ok(answer), None) => {
+                        missing += 1;
+                        format!("{}, got {answer}", colored("missing", YELLOW))
+                    }
+                    (Err(err), _) => {
+                        failed += 1;
+                        // The day and part are already shown at the start of the line
+                        let err = Error {
+                            day: None,
+                            part: None,
+                            ..err
+                        };
+                        format!("{}, {err}", colored("fail", RED))
+                    }
+                };
+                println!(
+                    "Day {:>2}: part {}: {:<8} {status}",
+                    day,
+                    part.number(),
+                    kind
+                );
+            }
+        }
+    }
+
+    println!(
+        "{}, {}, {}",
+        colored(&format!("{passed} passed"), GREEN),
+        colored(
+            &format!("{failed} failed"),
+            if failed > 0 { RED } else { GREEN }
+        ),
+        colored(&format!("{missing} missing"), YELLOW)
+    );
+    failed == 0
+}
+
+// Downloads every input that isn't there yet, and with `--sample` extracts the
+// samples from the puzzle pages
+pub fn fetch(
+    solutions: &[&dyn DynSolution],
+    paths: &InputPaths,
+    client: &Client,
+    args: &FetchArgs,
+) -> bool {
+    let mut success = true;
+
+    for solution in solutions {
+        let (year, day) = (solution.year(), solution.day());
+        let mut results = vec![(
+            "input",
+            paths.input_file(day, "input"),
+            fetch_to(&paths.input_file(day, "input"), || client.input(year, day)),
+        )];
+        if args.sample {
+            let path = paths.input_file(day, "sample");
+            let fetched = fetch_to(&path, || {
+                let page = client.puzzle_page(year, day)?;
+                extract_sample(&page)
+                    .ok_or_else(|| Error::new("the puzzle page has no sample input"))
+            });
+            results.push(("sample", path, fetched));
+        }
+
+        for (kind, path, fetched) in results {
+            match fetched {
+                Ok(Fetched::Downloaded) => {
+                    println!("Day {day:>2}: {kind:<6} saved to {}", path.display())
+                }
+                Ok(Fetched::Cached) => {
+                    println!("Day {day:>2}: {kind:<6} already in {}", path.display())
+                }
+                Err(err) => {
+                    eprintln!("error: {}", err.with_day(day));
+                    success = false;
+                }
+            }
+        }
+    }
+    success
+}
+
+// Solves the part on the real input and submits the answer, unless the
+// earlier submissions already tell that it is wrong
+fn submit_answer(
+    solution: &dyn DynSolution,
+    paths: &InputPaths,
+    client: &Client,
+    part: Part,
+) -> Result<Verdict> {
+    let (year, day) = (solution.year(), solution.day());
+    let input = read_input(&paths.input_file(day, "input"))?;
+    let answer = solution.run(part, &input)?;
+    println!("Day {day:>2}: part {}: {answer:>16}", part.number());
+
+    let history_file = paths.history_file();
+    let mut history = History::load(&history_file)?;
+    if let Some(reason) = history.check(year, day, part, &answer) {
+        return Err(Error::new(format!("not submitting, {reason}")));
+    }
+
+    let verdict = client.submit(year, day, part, &answer)?;
+    history.record(year, day, part, &answer, verdict.clone());
+    history.save(&history_file)?;
+    Ok(verdict)
+}
+
+pub fn submit(solution: &dyn DynSolution, paths: &InputPaths, client: &Client, part: Part) -> bool {
+    match submit_answer(solution, paths, client, part) {
+        Ok(verdict) => {
+            let color = match verdict {
+                Verdict::Correct => GREEN,
+                Verdict::Wait(_) | Verdict::WrongLevel => YELLOW,
+                _ => RED,
+            };
+            println!("{}", colored(&verdict.to_string(), color));
+            verdict == Verdict::Correct
+        }
+        Err(err) => {
+            eprintln!(
+                "error: {}",
+                err.with_day(solution.day()).with_part(part.number())
+            );
+            false
+        }
+    }
+}
+
+pub fn list(solutions: &[&dyn DynSolution]) {
+    for solution in solutions {
+        println!("{} day {:>2}", solution.year(), solution.day());
+    }
+}
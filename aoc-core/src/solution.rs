@@ -0,0 +1,345 @@
+use std::{
+    collections::{btree_map, BTreeMap},
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    error::{Error, Result},
+    visualize::{Observer, Palette},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+// Values that a puzzle only gives in its text, and that differ between the
+// sample and the real input, e.g. the row to look at on day 15. They are
+// recorded next to the answers of the samples, and a day uses the values of
+// the real puzzle for the ones that are not given.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Params(BTreeMap<String, i64>);
+
+impl Params {
+    pub fn get<T: TryFrom<i64>>(&self, name: &str, default: T) -> Result<T> {
+        match self.0.get(name) {
+            Some(value) => T::try_from(*value)
+                .map_err(|_| Error::new(format!("parameter {name} = {value} is out of range"))),
+            None => Ok(default),
+        }
+    }
+}
+
+impl<'a> FromIterator<(&'a str, i64)> for Params {
+    fn from_iter<I: IntoIterator<Item = (&'a str, i64)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(name, value)| (name.to_owned(), value))
+                .collect(),
+        )
+    }
+}
+
+pub trait Solution {
+    const YEAR: u16;
+    const DAY: u8;
+
+    // The parsed input, which may borrow from the raw text
+    type Input<'a>;
+    type Output1: Display;
+    type Output2: Display;
+
+    // The colors of the day's frames when they are rendered to images
+    const PALETTE: Palette = Palette::DEFAULT;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>>;
+
+    // Days with values in `Params` override this to read them
+    fn parse_with_params<'a>(&self, input: &'a str, _params: &Params) -> Result<Self::Input<'a>> {
+        self.parse(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Result<Self::Output1>;
+    fn part2(&self, input: &Self::Input<'_>) -> Result<Self::Output2>;
+
+    // Days that simulate something override these to solve the part while
+    // showing the simulation on `observer`
+    fn visualize_part1(
+        &self,
+        _input: &Self::Input<'_>,
+        _observer: &mut dyn Observer,
+    ) -> Option<Result<Self::Output1>> {
+        None
+    }
+
+    fn visualize_part2(
+        &self,
+        _input: &Self::Input<'_>,
+        _observer: &mut dyn Observer,
+    ) -> Option<Result<Self::Output2>> {
+        None
+    }
+}
+
+// Object safe version of `Solution`, so that days with different input and
+// output types can be stored next to each other in the registry.
+pub trait DynSolution {
+    fn year(&self) -> u16;
+    fn day(&self) -> u8;
+    fn palette(&self) -> Palette;
+    // The parsed input can be used for any number of runs of both parts
+    fn parse<'a>(&'a self, input: &'a str, params: &Params) -> Result<Box<dyn Parsed + 'a>>;
+
+    // Solves the part of a real input
+    fn run(&self, part: Part, input: &str) -> Result<String> {
+        self.parse(input, &Params::default())?.run(part)
+    }
+}
+
+// The input of a day after parsing, together with the day that solves it
+pub trait Parsed {
+    fn run(&self, part: Part) -> Result<String>;
+    // Returns `None` if the day has nothing to visualize
+    fn visualize(&self, part: Part, observer: &mut dyn Observer) -> Option<Result<String>>;
+}
+
+struct ParsedInput<'a, S: Solution> {
+    solution: &'a S,
+    input: S::Input<'a>,
+}
+
+impl<S: Solution> Parsed for ParsedInput<'_, S> {
+    fn run(&self, part: Part) -> Result<String> {
+        match part {
+            Part::One => self.solution.part1(&self.input).map(|a| a.to_string()),
+            Part::Two => self.solution.part2(&self.input).map(|a| a.to_string()),
+        }
+        .map_err(|err| err.with_day(S::DAY).with_part(part.number()))
+    }
+
+    fn visualize(&self, part: Part, observer: &mut dyn Observer) -> Option<Result<String>> {
+        let result = match part {
+            Part::One => self
+                .solution
+                .visualize_part1(&self.input, observer)?
+                .map(|answer| answer.to_string()),
+            Part::Two => self
+                .solution
+                .visualize_part2(&self.input, observer)?
+                .map(|answer| answer.to_string()),
+        };
+        Some(result.map_err(|err| err.with_day(S::DAY).with_part(part.number())))
+    }
+}
+
+impl<S: Solution> DynSolution for S {
+    fn year(&self) -> u16 {
+        S::YEAR
+    }
+
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn palette(&self) -> Palette {
+        S::PALETTE
+    }
+
+    fn parse<'a>(&'a self, input: &'a str, params: &Params) -> Result<Box<dyn Parsed + 'a>> {
+        let input = self
+            .parse_with_params(input, params)
+            .map_err(|err| err.with_day(S::DAY))?;
+        Ok(Box::new(ParsedInput {
+            solution: self,
+            input,
+        }))
+    }
+}
+
+#[derive(Default)]
+pub struct Registry {
+    solutions: BTreeMap<(u16, u8), Box<dyn DynSolution>>,
+    // The directory holding the inputs and answers of each year
+    resource_dirs: BTreeMap<u16, PathBuf>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_year(&mut self, year: u16, resource_dir: impl Into<PathBuf>) -> &mut Self {
+        let previous = self.resource_dirs.insert(year, resource_dir.into());
+        assert!(previous.is_none(), "Year {year} is added twice");
+        self
+    }
+
+    pub fn resource_dir(&self, year: u16) -> Option<&Path> {
+        self.resource_dirs.get(&year).map(PathBuf::as_path)
+    }
+
+    pub fn years(&self) -> impl Iterator<Item = u16> + '_ {
+        self.resource_dirs.keys().copied()
+    }
+
+    pub fn register<S: Solution + 'static>(&mut self, solution: S) -> &mut Self {
+        let previous = self.solutions.insert((S::YEAR, S::DAY), Box::new(solution));
+        assert!(
+            previous.is_none(),
+            "Day {} of {} is registered twice",
+            S::DAY,
+            S::YEAR
+        );
+        self
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Option<&dyn DynSolution> {
+        self.solutions.get(&(year, day)).map(|s| s.as_ref())
+    }
+
+    // Iterates over all solutions, ordered by year and then day.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.solutions.values(),
+        }
+    }
+}
+
+pub struct Iter<'a> {
+    inner: btree_map::Values<'a, (u16, u8), Box<dyn DynSolution>>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a dyn DynSolution;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|s| s.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::Error, visualize::NoObserver};
+
+    struct TestDay;
+
+    impl Solution for TestDay {
+        const YEAR: u16 = 2000;
+        const DAY: u8 = 3;
+
+        type Input<'a> = Vec<&'a str>;
+        type Output1 = usize;
+        type Output2 = String;
+
+        fn parse<'a>(&self, input: &'a str) -> Result<Vec<&'a str>> {
+            Ok(input.lines().collect())
+        }
+
+        fn part1(&self, input: &Vec<&str>) -> Result<usize> {
+            Ok(input.len())
+        }
+
+        fn part2(&self, input: &Vec<&str>) -> Result<String> {
+            Ok(input.concat())
+        }
+    }
+
+    struct OtherTestDay;
+
+    impl Solution for OtherTestDay {
+        const YEAR: u16 = 2000;
+        const DAY: u8 = 1;
+
+        type Input<'a> = ();
+        type Output1 = u8;
+        type Output2 = u8;
+
+        fn parse(&self, input: &str) -> Result<()> {
+            match input {
+                "invalid" => Err(Error::at_line(1, "invalid input")),
+                _ => Ok(()),
+            }
+        }
+
+        fn part1(&self, _input: &()) -> Result<u8> {
+            Ok(1)
+        }
+
+        fn part2(&self, _input: &()) -> Result<u8> {
+            Err(Error::at_line(2, "invalid input"))
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = Registry::new();
+        registry.register(TestDay).register(OtherTestDay);
+
+        assert_eq!(
+            registry.iter().map(|s| s.day()).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert!(registry.get(2000, 2).is_none());
+        assert!(registry.get(2001, 3).is_none());
+
+        let solution = registry.get(2000, 3).unwrap();
+        let input = solution.parse("a\nb\n", &Params::default()).unwrap();
+        assert_eq!(input.run(Part::One), Ok("2".to_string()));
+        assert_eq!(input.run(Part::Two), Ok("ab".to_string()));
+        assert!(input.visualize(Part::One, &mut NoObserver).is_none());
+        assert_eq!(solution.run(Part::One, "a"), Ok("1".to_string()));
+    }
+
+    #[test]
+    fn test_years() {
+        let mut registry = Registry::new();
+        registry.add_year(2001, "res2001").add_year(2000, "res2000");
+
+        assert_eq!(registry.years().collect::<Vec<_>>(), vec![2000, 2001]);
+        assert_eq!(registry.resource_dir(2001), Some(Path::new("res2001")));
+        assert_eq!(registry.resource_dir(2002), None);
+    }
+
+    #[test]
+    fn test_run_adds_context() {
+        let mut registry = Registry::new();
+        registry.register(OtherTestDay);
+
+        let solution = registry.get(2000, 1).unwrap();
+        let err = solution.run(Part::Two, "").unwrap_err();
+        assert_eq!(err.to_string(), "day 1, part 2, line 2: invalid input");
+        let err = solution.run(Part::One, "invalid").unwrap_err();
+        assert_eq!(err.to_string(), "day 1, line 1: invalid input");
+    }
+
+    #[test]
+    fn test_params() {
+        let params: Params = [("row", 10), ("size", 1 << 40)].into_iter().collect();
+        assert_eq!(params.get("row", 5i32), Ok(10));
+        assert_eq!(params.get("column", 5i32), Ok(5));
+        assert!(params.get("size", 0i32).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_register_twice() {
+        Registry::new().register(TestDay).register(TestDay);
+    }
+}
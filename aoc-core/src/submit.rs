@@ -0,0 +1,269 @@
+use std::{
+    fmt, fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    fetch::Client,
+    solution::Part,
+};
+
+// Kept next to the real inputs, since the answers depend on them
+pub const HISTORY_FILE: &str = "submissions.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    // Submitted too soon after a wrong answer, with the time left to wait
+    Wait(String),
+    // The part has been solved already, or the first part isn't solved yet
+    WrongLevel,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "that's the right answer"),
+            Verdict::Wrong => write!(f, "that's not the right answer"),
+            Verdict::TooHigh => write!(f, "that's not the right answer, it is too high"),
+            Verdict::TooLow => write!(f, "that's not the right answer, it is too low"),
+            Verdict::Wait(time) => write!(f, "an answer was given too recently, wait {time}"),
+            Verdict::WrongLevel => write!(f, "this part can't be answered right now"),
+        }
+    }
+}
+
+impl Verdict {
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+// Reads the verdict from the page that the site responds with
+pub fn parse_response(html: &str) -> Result<Verdict> {
+    if html.contains("That's the right answer") {
+        Ok(Verdict::Correct)
+    } else if html.contains("That's not the right answer") {
+        Ok(if html.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if html.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        })
+    } else if html.contains("You gave an answer too recently") {
+        let time = html
+            .split_once("You have ")
+            .and_then(|(_, rest)| rest.split_once(" left to wait"))
+            .map_or("a while", |(time, _)| time);
+        Ok(Verdict::Wait(time.to_owned()))
+    } else if html.contains("You don't seem to be solving the right level") {
+        Ok(Verdict::WrongLevel)
+    } else {
+        Err(Error::new(
+            "could not understand the response to the answer",
+        ))
+    }
+}
+
+impl Client {
+    pub fn submit(&self, year: u16, day: u8, part: Part, answer: &str) -> Result<Verdict> {
+        let request = self.request("POST", &format!("/{year}/day/{day}/answer"))?;
+        let level = part.number().to_string();
+        let page = self.send(request, Some(&[("level", &level), ("answer", answer)]))?;
+        parse_response(&page)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    // Seconds since the Unix epoch
+    pub time: u64,
+}
+
+// Every answer that has been submitted, so that answers that are known to be
+// wrong are not sent again
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct History {
+    submissions: Vec<Submission>,
+}
+
+impl History {
+    // Returns an empty history if the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|err| Error::new(format!("invalid history {}: {err}", path.display())))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|err| Error::new(format!("could not serialize history: {err}")))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(fs::write(path, content + "\n")?)
+    }
+
+    pub fn submissions(&self, year: u16, day: u8, part: Part) -> impl Iterator<Item = &Submission> {
+        self.submissions
+            .iter()
+            .filter(move |s| s.year == year && s.day == day && s.part == part.number())
+    }
+
+    // Returns why the answer shouldn't be submitted, if the earlier
+    // submissions already tell whether it is right
+    pub fn check(&self, year: u16, day: u8, part: Part, answer: &str) -> Option<String> {
+        let number = answer.parse::<i128>().ok();
+        self.submissions(year, day, part)
+            .find_map(|s| match s.verdict {
+                Verdict::Correct => Some(format!("the part is already solved with {}", s.answer)),
+                _ if s.verdict.is_wrong() && s.answer == answer => {
+                    Some(format!("{answer} was already submitted: {}", s.verdict))
+                }
+                Verdict::TooHigh => number
+                    .zip(s.answer.parse::<i128>().ok())
+                    .filter(|(number, high)| number >= high)
+                    .map(|_| format!("{answer} is too high, {} already was", s.answer)),
+                Verdict::TooLow => number
+                    .zip(s.answer.parse::<i128>().ok())
+                    .filter(|(number, low)| number <= low)
+                    .map(|_| format!("{answer} is too low, {} already was", s.answer)),
+                _ => None,
+            })
+    }
+
+    pub fn record(&mut self, year: u16, day: u8, part: Part, answer: &str, verdict: Verdict) {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+        self.submissions.push(Submission {
+            year,
+            day,
+            part: part.number(),
+            answer: answer.to_owned(),
+            verdict,
+            time,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{mock_server, temp_dir};
+
+    #[test]
+    fn test_parse_response() {
+        let page = |text: &str| format!("<main><article><p>{text}</p></article></main>");
+        assert_eq!(
+            parse_response(&page(
+                "That's the right answer! You are one gold star closer."
+            )),
+            Ok(Verdict::Correct)
+        );
+        assert_eq!(
+            parse_response(&page(
+                "That's not the right answer; your answer is too high."
+            )),
+            Ok(Verdict::TooHigh)
+        );
+        assert_eq!(
+            parse_response(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Ok(Verdict::TooLow)
+        );
+        assert_eq!(
+            parse_response(&page("That's not the right answer. If you're stuck, ...")),
+            Ok(Verdict::Wrong)
+        );
+        assert_eq!(
+            parse_response(&page(
+                "You gave an answer too recently. You have 38s left to wait."
+            )),
+            Ok(Verdict::Wait("38s".to_owned()))
+        );
+        assert_eq!(
+            parse_response(&page("You don't seem to be solving the right level.")),
+            Ok(Verdict::WrongLevel)
+        );
+        assert!(parse_response(&page("Something else")).is_err());
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, requests) = mock_server(vec![(
+            200,
+            "",
+            "<article><p>That's the right answer!</p></article>",
+        )]);
+        let client = Client::new(&url, Some("abc".to_owned()));
+
+        assert_eq!(
+            client.submit(2022, 5, Part::Two, "CMZ"),
+            Ok(Verdict::Correct)
+        );
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "POST /2022/day/5/answer HTTP/1.1");
+        assert_eq!(request.cookie.as_deref(), Some("session=abc"));
+        assert_eq!(request.body, "level=2&answer=CMZ");
+    }
+
+    #[test]
+    fn test_check() {
+        let mut history = History::default();
+        history.record(2022, 1, Part::One, "100", Verdict::TooHigh);
+        history.record(2022, 1, Part::One, "10", Verdict::TooLow);
+        history.record(2022, 1, Part::One, "50", Verdict::Wrong);
+        history.record(2022, 1, Part::One, "60", Verdict::Wait("1m".to_owned()));
+
+        let check = |answer| history.check(2022, 1, Part::One, answer);
+        assert!(check("100").is_some());
+        assert!(check("150").is_some());
+        assert!(check("10").is_some());
+        assert!(check("-3").is_some());
+        assert!(check("50").is_some());
+        assert_eq!(check("60"), None);
+        assert_eq!(check("51"), None);
+        assert_eq!(check("abc"), None);
+        assert_eq!(history.check(2022, 1, Part::Two, "100"), None);
+        assert_eq!(history.check(2021, 1, Part::One, "100"), None);
+
+        history.record(2022, 1, Part::One, "42", Verdict::Correct);
+        assert!(history.check(2022, 1, Part::One, "43").is_some());
+    }
+
+    #[test]
+    fn test_load_and_save() {
+        let dir = temp_dir("history");
+        let path = dir.join("submissions.json");
+        assert_eq!(History::load(&path), Ok(History::default()));
+
+        let mut history = History::default();
+        history.record(2022, 3, Part::Two, "1234", Verdict::TooLow);
+        history.save(&path).unwrap();
+        let loaded = History::load(&path).unwrap();
+        assert_eq!(loaded, history);
+        assert_eq!(loaded.submissions(2022, 3, Part::Two).count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
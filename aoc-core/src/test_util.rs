@@ -0,0 +1,81 @@
+// Helpers shared by the tests of several modules
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    path::PathBuf,
+    process,
+    sync::mpsc,
+    thread,
+};
+
+// A request as seen by the mock server
+#[derive(Debug)]
+pub struct Request {
+    pub line: String,
+    pub cookie: Option<String>,
+    pub body: String,
+}
+
+// Answers each of the next requests with one of `responses`, as
+// (status, extra headers, body), and reports the requests it got.
+// Returns the base URL of the server.
+pub fn mock_server(
+    responses: Vec<(u16, &'static str, &'static str)>,
+) -> (String, mpsc::Receiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for (status, headers, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+
+            let mut cookie = None;
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                let header = header.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                let (name, value) = header.split_once(": ").unwrap();
+                match name.to_lowercase().as_str() {
+                    "cookie" => cookie = Some(value.to_owned()),
+                    "content-length" => content_length = value.parse().unwrap(),
+                    _ => {}
+                }
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            let mut stream = reader.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n{headers}\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            sender
+                .send(Request {
+                    line: line.trim_end().to_owned(),
+                    cookie,
+                    body: String::from_utf8(request_body).unwrap(),
+                })
+                .unwrap();
+        }
+    });
+    (url, receiver)
+}
+
+// An empty directory for a test to write to, unique to the test run
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("aoc-{name}-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,42 @@
+use std::{fs, io, path::Path, sync::OnceLock};
+
+use crate::error::{Error, Result};
+
+pub mod automaton;
+pub mod branch_and_bound;
+pub mod cycle;
+pub mod expr;
+mod grid;
+mod interval_set;
+pub mod parse;
+mod ring;
+pub mod search;
+mod vector;
+mod voxels;
+
+pub use grid::Grid;
+pub use interval_set::IntervalSet;
+pub use ring::Ring;
+pub use vector::{ParseVectorError, Point, Vector};
+pub use voxels::{Voxel, Voxels};
+
+// Reads a file, or stdin if the path is `-`. Stdin is only read once, so the
+// same input can be loaded several times.
+pub fn read_input(path: &Path) -> Result<String> {
+    static STDIN: OnceLock<Result<String>> = OnceLock::new();
+    if path == Path::new("-") {
+        return STDIN
+            .get_or_init(|| {
+                io::read_to_string(io::stdin().lock())
+                    .map_err(|err| Error::new(format!("could not read stdin: {err}")))
+            })
+            .clone();
+    }
+
+    fs::read_to_string(path).map_err(|err| {
+        Error::new(format!(
+            "could not open input file {}: {err}",
+            path.display()
+        ))
+    })
+}
@@ -0,0 +1,428 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::util::{Grid, Point};
+
+// The smallest rectangle that contains all live cells, including both corners
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min: Point<i32>,
+    pub max: Point<i32>,
+}
+
+impl BoundingBox {
+    fn of(mut points: impl Iterator<Item = Point<i32>>) -> Option<Self> {
+        let first = points.next()?;
+        Some(points.fold(
+            Self {
+                min: first,
+                max: first,
+            },
+            |bounds, p| Self {
+                min: Point::new(bounds.min.x().min(p.x()), bounds.min.y().min(p.y())),
+                max: Point::new(bounds.max.x().max(p.x()), bounds.max.y().max(p.y())),
+            },
+        ))
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x() - self.min.x() + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y() - self.min.y() + 1) as usize
+    }
+
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+}
+
+pub trait Automaton {
+    // The number of generations that have been computed so far
+    fn generation(&self) -> usize;
+
+    // The bounding box of the live cells, or `None` if there are none
+    fn bounding_box(&self) -> Option<BoundingBox>;
+}
+
+// Runs `n` generations. `step` computes the next generation and `observe` is
+// called after each one with its bounding box.
+pub fn run<A: Automaton>(
+    automaton: &mut A,
+    n: usize,
+    mut step: impl FnMut(&mut A) -> bool,
+    mut observe: impl FnMut(&A, Option<BoundingBox>),
+) {
+    for _ in 0..n {
+        step(automaton);
+        observe(automaton, automaton.bounding_box());
+    }
+}
+
+// Runs until `step` reports that nothing changed, and returns the number of
+// generations run, including the last one. `observe` is called after every
+// generation, also the last one.
+pub fn run_until_stable<A: Automaton>(
+    automaton: &mut A,
+    mut step: impl FnMut(&mut A) -> bool,
+    mut observe: impl FnMut(&A, Option<BoundingBox>),
+) -> usize {
+    let mut generations = 0;
+    loop {
+        let changed = step(automaton);
+        generations += 1;
+        observe(automaton, automaton.bounding_box());
+        if !changed {
+            return generations;
+        }
+    }
+}
+
+// A set of live cells on an unbounded plane
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sparse {
+    cells: HashSet<Point<i32>>,
+    generation: usize,
+}
+
+impl Sparse {
+    pub fn new(cells: impl IntoIterator<Item = Point<i32>>) -> Self {
+        Self {
+            cells: cells.into_iter().collect(),
+            generation: 0,
+        }
+    }
+
+    pub fn from_grid(grid: &Grid<bool>) -> Self {
+        Self::new(grid.iter().filter(|(_, alive)| **alive).map(|(p, _)| p))
+    }
+
+    pub fn cells(&self) -> &HashSet<Point<i32>> {
+        &self.cells
+    }
+
+    pub fn contains(&self, p: Point<i32>) -> bool {
+        self.cells.contains(&p)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn live_neighbors(&self, p: Point<i32>) -> usize {
+        p.get_neighbors().filter(|n| self.cells.contains(n)).count()
+    }
+
+    // Decides with `rule` whether each live cell and each of their neighbours
+    // is alive in the next generation. Returns whether any cell changed.
+    pub fn step(&mut self, mut rule: impl FnMut(&Self, Point<i32>) -> bool) -> bool {
+        let candidates: HashSet<Point<i32>> = self
+            .cells
+            .iter()
+            .flat_map(|p| p.get_neighbors().chain([*p]))
+            .collect();
+        let next: HashSet<Point<i32>> = candidates.into_iter().filter(|p| rule(self, *p)).collect();
+
+        self.generation += 1;
+        let changed = next != self.cells;
+        self.cells = next;
+        changed
+    }
+
+    // Updates in two phases: every live cell may propose to move somewhere
+    // with `propose`, and then all cells move at once. A move is rejected if
+    // another cell proposed the same target, or if the target is occupied by
+    // a cell that doesn't move away. Returns whether any cell moved.
+    pub fn step_moves(
+        &mut self,
+        mut propose: impl FnMut(&Self, Point<i32>) -> Option<Point<i32>>,
+    ) -> bool {
+        let proposals: Vec<(Point<i32>, Point<i32>)> = self
+            .cells
+            .iter()
+            .filter_map(|p| {
+                propose(self, *p)
+                    .filter(|target| target != p)
+                    .map(|target| (*p, target))
+            })
+            .collect();
+
+        let mut num_proposals: HashMap<Point<i32>, usize> = HashMap::new();
+        for (_, target) in &proposals {
+            *num_proposals.entry(*target).or_default() += 1;
+        }
+        let mut moves: HashMap<Point<i32>, Point<i32>> = proposals
+            .into_iter()
+            .filter(|(_, target)| num_proposals[target] == 1)
+            .collect();
+
+        // A rejected move keeps its cell in place, which can block the moves
+        // onto that cell in turn
+        loop {
+            let blocked: Vec<Point<i32>> = moves
+                .iter()
+                .filter(|(_, target)| self.cells.contains(target) && !moves.contains_key(target))
+                .map(|(p, _)| *p)
+                .collect();
+            if blocked.is_empty() {
+                break;
+            }
+            for p in blocked {
+                moves.remove(&p);
+            }
+        }
+
+        for p in moves.keys() {
+            self.cells.remove(p);
+        }
+        self.cells.extend(moves.values());
+
+        self.generation += 1;
+        !moves.is_empty()
+    }
+}
+
+impl Automaton for Sparse {
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::of(self.cells.iter().copied())
+    }
+}
+
+// The states around one cell of a `Dense` automaton
+pub struct Neighborhood<'a, T> {
+    automaton: &'a Dense<T>,
+    center: Point<i32>,
+}
+
+impl<T> Neighborhood<'_, T> {
+    pub fn center(&self) -> Point<i32> {
+        self.center
+    }
+
+    pub fn state(&self) -> &T {
+        &self.automaton.grid[self.center]
+    }
+
+    // The state of the cell at `offset` from the center. Outside of the grid
+    // this is the background, unless the grid wraps around.
+    pub fn get(&self, offset: Point<i32>) -> &T {
+        self.automaton.get(self.center + offset)
+    }
+
+    // The eight cells around the center
+    pub fn neighbors(&self) -> impl Iterator<Item = &T> {
+        Point::new(0, 0)
+            .get_neighbors()
+            .map(|offset| self.get(offset))
+    }
+}
+
+// A grid where every cell has a state. Cells outside of the grid are either
+// in the background state or the grid wraps around at its edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dense<T> {
+    grid: Grid<T>,
+    background: T,
+    wraps: bool,
+    generation: usize,
+}
+
+impl<T> Dense<T> {
+    pub fn new(grid: Grid<T>, background: T) -> Self {
+        Self {
+            grid,
+            background,
+            wraps: false,
+            generation: 0,
+        }
+    }
+
+    pub fn wrapping(mut self) -> Self {
+        self.wraps = true;
+        self
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn get(&self, p: Point<i32>) -> &T {
+        if self.wraps {
+            &self.grid[Point::new(
+                p.x().rem_euclid(self.grid.width() as i32),
+                p.y().rem_euclid(self.grid.height() as i32),
+            )]
+        } else {
+            self.grid.get(p).unwrap_or(&self.background)
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Dense<T> {
+    // Computes the next state of every cell with `rule`. Returns whether any
+    // cell changed.
+    pub fn step(&mut self, mut rule: impl FnMut(&Neighborhood<T>) -> T) -> bool {
+        let mut next = self.grid.clone();
+        for p in self.grid.points() {
+            next[p] = rule(&Neighborhood {
+                automaton: self,
+                center: p,
+            });
+        }
+
+        self.generation += 1;
+        let changed = next != self.grid;
+        self.grid = next;
+        changed
+    }
+}
+
+impl<T: Clone + PartialEq> Automaton for Dense<T> {
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // The bounding box of the cells that are not in the background state
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::of(
+            self.grid
+                .iter()
+                .filter(|(_, state)| **state != self.background)
+                .map(|(p, _)| p),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(automaton: &Sparse, p: Point<i32>) -> bool {
+        matches!(
+            (automaton.contains(p), automaton.live_neighbors(p)),
+            (true, 2) | (_, 3)
+        )
+    }
+
+    fn parse(input: &[&str]) -> Grid<bool> {
+        Grid::parse(&input.join("\n"), |c| Some(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn test_sparse() {
+        // A glider moves one cell down and to the right every four
+        // generations
+        let mut glider = Sparse::from_grid(&parse(&[".#.", "..#", "###"]));
+        let start = glider.bounding_box().unwrap();
+
+        let mut boxes = Vec::new();
+        run(
+            &mut glider,
+            8,
+            |a| a.step(life),
+            |_, bounds| boxes.push(bounds),
+        );
+        assert_eq!(glider.generation(), 8);
+        assert_eq!(glider.len(), 5);
+        assert_eq!(boxes.len(), 8);
+        assert_eq!(
+            boxes[7],
+            Some(BoundingBox {
+                min: start.min + Point::new(2, 2),
+                max: start.max + Point::new(2, 2),
+            })
+        );
+
+        // A block never changes, but its only generation is still observed
+        let mut block = Sparse::from_grid(&parse(&["##", "##"]));
+        let mut observed = 0;
+        assert_eq!(
+            run_until_stable(&mut block, |a| a.step(life), |_, _| observed += 1),
+            1
+        );
+        assert_eq!(observed, 1);
+        assert_eq!(block.bounding_box().unwrap().area(), 4);
+    }
+
+    #[test]
+    fn test_step_moves() {
+        // Both cells want to move to (1, 0), so neither moves, and the
+        // cell at (5, 5) moves on its own
+        let mut automaton = Sparse::new([Point::new(0, 0), Point::new(2, 0), Point::new(5, 5)]);
+        let moved = automaton.step_moves(|_, p| match p {
+            p if p == Point::new(5, 5) => Some(Point::new(5, 6)),
+            _ => Some(Point::new(1, 0)),
+        });
+        assert!(moved);
+        assert!(automaton.contains(Point::new(0, 0)));
+        assert!(automaton.contains(Point::new(2, 0)));
+        assert!(automaton.contains(Point::new(5, 6)));
+        assert_eq!(automaton.len(), 3);
+
+        assert!(!automaton.step_moves(|_, _| None));
+        assert_eq!(automaton.generation(), 2);
+
+        // Staying in place is not a move
+        assert!(!automaton.step_moves(|_, p| Some(p)));
+    }
+
+    #[test]
+    fn test_step_moves_blocked() {
+        // The cell at (2, 0) stays, which blocks the cell at (1, 0) and then
+        // the one at (0, 0) behind it
+        let cells = [Point::new(0, 0), Point::new(1, 0), Point::new(2, 0)];
+        let mut automaton = Sparse::new(cells);
+        let moved = automaton.step_moves(|_, p| (p.x() < 2).then(|| p + Point::new(1, 0)));
+        assert!(!moved);
+        assert_eq!(
+            automaton,
+            Sparse {
+                cells: cells.into(),
+                generation: 1
+            }
+        );
+
+        // When the front cell moves away, the others can follow it
+        let moved = automaton.step_moves(|_, p| Some(p + Point::new(1, 0)));
+        assert!(moved);
+        assert_eq!(
+            automaton.cells(),
+            &HashSet::from([Point::new(1, 0), Point::new(2, 0), Point::new(3, 0)])
+        );
+    }
+
+    #[test]
+    fn test_dense() {
+        // Every live cell moves one step to the right
+        let shift = |n: &Neighborhood<bool>| *n.get(Point::new(-1, 0));
+
+        let mut automaton = Dense::new(parse(&["#..", ".#."]), false);
+        automaton.step(shift);
+        assert_eq!(automaton.grid(), &parse(&[".#.", "..#"]));
+        automaton.step(shift);
+        assert_eq!(automaton.grid(), &parse(&["..#", "..."]));
+        assert_eq!(
+            automaton.bounding_box(),
+            Some(BoundingBox {
+                min: Point::new(2, 0),
+                max: Point::new(2, 0),
+            })
+        );
+        let generations = run_until_stable(&mut automaton, |a| a.step(shift), |_, _| {});
+        assert_eq!(generations, 2);
+        assert_eq!(automaton.bounding_box(), None);
+
+        let mut automaton = Dense::new(parse(&["#..", ".#."]), false).wrapping();
+        run(&mut automaton, 3, |a| a.step(shift), |_, _| {});
+        assert_eq!(automaton.grid(), &parse(&["#..", ".#."]));
+        assert_eq!(automaton.generation(), 3);
+    }
+}
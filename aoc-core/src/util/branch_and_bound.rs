@@ -0,0 +1,260 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    DepthFirst,
+    // Always expands the state with the highest upper bound
+    BestFirst,
+    // Expands the search level by level and only keeps the given number of
+    // states with the highest upper bounds on each level. Much faster, but
+    // can miss the optimal solution.
+    Beam(usize),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    // States whose successors have been generated
+    pub expanded: usize,
+    // States that were dropped because they can't beat the best score
+    pub pruned: usize,
+    // The number of times a better score was found
+    pub improvements: usize,
+}
+
+struct Node<S, C> {
+    bound: C,
+    state: S,
+}
+
+impl<S, C: PartialEq> PartialEq for Node<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bound == other.bound
+    }
+}
+
+impl<S, C: Eq> Eq for Node<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Node<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for Node<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bound.cmp(&other.bound)
+    }
+}
+
+// Finds the state with the highest score. Every state is a valid solution
+// with the given score, and `upper_bound` must never be lower than the score
+// of the state itself or of any state that can be reached from it.
+pub struct BranchAndBound {
+    strategy: Strategy,
+    stats: Stats,
+}
+
+impl BranchAndBound {
+    pub fn new(strategy: Strategy) -> Self {
+        Self {
+            strategy,
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    // Returns the best state and its score
+    pub fn maximize<S, C, I>(
+        &mut self,
+        start: S,
+        mut expand: impl FnMut(&S) -> I,
+        mut upper_bound: impl FnMut(&S) -> C,
+        mut score: impl FnMut(&S) -> C,
+    ) -> (S, C)
+    where
+        S: Clone,
+        C: Copy + Ord,
+        I: IntoIterator<Item = S>,
+    {
+        let mut best_score = score(&start);
+        let mut best = start.clone();
+        let mut frontier = Frontier::new(self.strategy);
+        frontier.push(Node {
+            bound: upper_bound(&start),
+            state: start,
+        });
+
+        while let Some(Node { bound, state }) = frontier.pop() {
+            // The best score may have improved since the state was added
+            if bound <= best_score {
+                self.stats.pruned += 1;
+                continue;
+            }
+
+            self.stats.expanded += 1;
+            for next in expand(&state) {
+                let next_score = score(&next);
+                if next_score > best_score {
+                    best_score = next_score;
+                    best = next.clone();
+                    self.stats.improvements += 1;
+                }
+
+                let bound = upper_bound(&next);
+                if bound > best_score {
+                    frontier.push(Node { bound, state: next });
+                } else {
+                    self.stats.pruned += 1;
+                }
+            }
+        }
+        self.stats.pruned += frontier.dropped();
+        (best, best_score)
+    }
+}
+
+enum Frontier<S, C> {
+    Stack(Vec<Node<S, C>>),
+    Heap(BinaryHeap<Node<S, C>>),
+    Beam {
+        width: usize,
+        level: Vec<Node<S, C>>,
+        next_level: Vec<Node<S, C>>,
+        dropped: usize,
+    },
+}
+
+impl<S, C: Ord> Frontier<S, C> {
+    fn new(strategy: Strategy) -> Self {
+        match strategy {
+            Strategy::DepthFirst => Self::Stack(Vec::new()),
+            Strategy::BestFirst => Self::Heap(BinaryHeap::new()),
+            Strategy::Beam(width) => Self::Beam {
+                width,
+                level: Vec::new(),
+                next_level: Vec::new(),
+                dropped: 0,
+            },
+        }
+    }
+
+    fn push(&mut self, node: Node<S, C>) {
+        match self {
+            Self::Stack(stack) => stack.push(node),
+            Self::Heap(heap) => heap.push(node),
+            Self::Beam { next_level, .. } => next_level.push(node),
+        }
+    }
+
+    fn pop(&mut self) -> Option<Node<S, C>> {
+        match self {
+            Self::Stack(stack) => stack.pop(),
+            Self::Heap(heap) => heap.pop(),
+            Self::Beam {
+                width,
+                level,
+                next_level,
+                dropped,
+            } => {
+                if level.is_empty() {
+                    // Starts the next level with the best states, in
+                    // ascending order since they are popped from the back
+                    next_level.sort();
+                    let excess = next_level.len().saturating_sub(*width);
+                    next_level.drain(..excess);
+                    *dropped += excess;
+                    std::mem::swap(level, next_level);
+                }
+                level.pop()
+            }
+        }
+    }
+
+    // The number of states that didn't fit into the beam
+    fn dropped(&self) -> usize {
+        match self {
+            Self::Beam { dropped, .. } => *dropped,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 0/1 knapsack where each state is the set of items taken so far
+    const ITEMS: [(u32, u32); 6] = [(10, 5), (40, 4), (30, 6), (50, 3), (35, 5), (5, 1)];
+    const CAPACITY: u32 = 12;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Knapsack {
+        next_item: usize,
+        weight: u32,
+        value: u32,
+    }
+
+    fn solve(strategy: Strategy) -> (Knapsack, u32, Stats) {
+        let start = Knapsack {
+            next_item: 0,
+            weight: 0,
+            value: 0,
+        };
+        let expand = |state: &Knapsack| {
+            let mut next = Vec::new();
+            if let Some((value, weight)) = ITEMS.get(state.next_item) {
+                next.push(Knapsack {
+                    next_item: state.next_item + 1,
+                    ..state.clone()
+                });
+                if state.weight + weight <= CAPACITY {
+                    next.push(Knapsack {
+                        next_item: state.next_item + 1,
+                        weight: state.weight + weight,
+                        value: state.value + value,
+                    });
+                }
+            }
+            next
+        };
+        // Assumes that all remaining items still fit
+        let upper_bound = |state: &Knapsack| {
+            state.value
+                + ITEMS[state.next_item..]
+                    .iter()
+                    .map(|(value, _)| value)
+                    .sum::<u32>()
+        };
+
+        let mut search = BranchAndBound::new(strategy);
+        let (best, score) = search.maximize(start, expand, upper_bound, |state| state.value);
+        (best, score, search.stats())
+    }
+
+    #[test]
+    fn test_exact_strategies() {
+        for strategy in [Strategy::DepthFirst, Strategy::BestFirst] {
+            let (best, score, stats) = solve(strategy);
+            assert_eq!(score, 125);
+            assert_eq!(best.value, 125);
+            assert_eq!(best.weight, 12);
+            assert!(stats.expanded < 2usize.pow(ITEMS.len() as u32));
+            assert!(stats.pruned > 0);
+            assert!(stats.improvements > 0);
+        }
+    }
+
+    #[test]
+    fn test_beam() {
+        let (_, score, _) = solve(Strategy::Beam(64));
+        assert_eq!(score, 125);
+
+        let (best, score, stats) = solve(Strategy::Beam(1));
+        assert!(score <= 125);
+        assert_eq!(best.value, score);
+        assert!(stats.expanded <= ITEMS.len() + 1);
+    }
+}
@@ -0,0 +1,245 @@
+use std::{collections::HashMap, hash::Hash};
+
+use num::{NumCast, PrimInt};
+
+// The states from step `start` on repeat every `length` steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // The first step that has the same state as step `n`
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+// All of these assume that the fingerprint of a state determines the
+// fingerprints of all the states that follow, and that the states do
+// eventually repeat.
+
+// Floyd's tortoise and hare. Needs no extra memory, but has to clone the
+// state and steps it about three times as often as the cycle is long.
+pub fn floyd<S, K>(
+    start: &S,
+    mut step: impl FnMut(&mut S),
+    mut fingerprint: impl FnMut(&S) -> K,
+) -> Cycle
+where
+    S: Clone,
+    K: Eq,
+{
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    step(&mut tortoise);
+    step(&mut hare);
+    step(&mut hare);
+    while fingerprint(&tortoise) != fingerprint(&hare) {
+        step(&mut tortoise);
+        step(&mut hare);
+        step(&mut hare);
+    }
+
+    let mut tortoise = start.clone();
+    let mut cycle_start = 0;
+    while fingerprint(&tortoise) != fingerprint(&hare) {
+        step(&mut tortoise);
+        step(&mut hare);
+        cycle_start += 1;
+    }
+
+    let tortoise = fingerprint(&tortoise);
+    let mut length = 1;
+    step(&mut hare);
+    while fingerprint(&hare) != tortoise {
+        step(&mut hare);
+        length += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        length,
+    }
+}
+
+// Brent's algorithm. Like `floyd`, but needs fewer steps.
+pub fn brent<S, K>(
+    start: &S,
+    mut step: impl FnMut(&mut S),
+    mut fingerprint: impl FnMut(&S) -> K,
+) -> Cycle
+where
+    S: Clone,
+    K: Eq,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = fingerprint(start);
+    let mut hare = start.clone();
+    step(&mut hare);
+    loop {
+        let current = fingerprint(&hare);
+        if current == tortoise {
+            break;
+        }
+        if power == length {
+            tortoise = current;
+            power *= 2;
+            length = 0;
+        }
+        step(&mut hare);
+        length += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    for _ in 0..length {
+        step(&mut hare);
+    }
+    let mut cycle_start = 0;
+    while fingerprint(&tortoise) != fingerprint(&hare) {
+        step(&mut tortoise);
+        step(&mut hare);
+        cycle_start += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        length,
+    }
+}
+
+// Remembers the fingerprint of every state. Steps each state only once and
+// doesn't need to clone it.
+pub fn find_with_hash<S, K>(
+    mut state: S,
+    mut step: impl FnMut(&mut S),
+    mut fingerprint: impl FnMut(&S) -> K,
+) -> Cycle
+where
+    K: Eq + Hash,
+{
+    let mut seen = HashMap::new();
+    for n in 0.. {
+        if let Some(start) = seen.insert(fingerprint(&state), n) {
+            return Cycle {
+                start,
+                length: n - start,
+            };
+        }
+        step(&mut state);
+    }
+    unreachable!()
+}
+
+// Returns `metric` after `n` steps. Only steps until the first cycle is
+// found, and assumes that `metric` grows by the same amount every time the
+// cycle repeats, like the height of a tower that is built up in a pattern.
+pub fn extrapolate<S, K, M>(
+    mut state: S,
+    n: usize,
+    mut step: impl FnMut(&mut S),
+    mut fingerprint: impl FnMut(&S) -> K,
+    mut metric: impl FnMut(&S) -> M,
+) -> M
+where
+    K: Eq + Hash,
+    M: PrimInt,
+{
+    let mut seen = HashMap::new();
+    let mut metrics = Vec::new();
+
+    for i in 0..n {
+        metrics.push(metric(&state));
+        if let Some(start) = seen.insert(fingerprint(&state), i) {
+            let cycle = Cycle {
+                start,
+                length: i - start,
+            };
+            let repetitions: M = NumCast::from((n - start) / cycle.length)
+                .expect("the metric type is too small for the number of steps");
+            let gain = metrics[i] - metrics[start];
+            return metrics[cycle.equivalent_step(n)] + gain * repetitions;
+        }
+        step(&mut state);
+    }
+    metric(&state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counts up from 0 to 9 and then continues from 3, while adding up all
+    // the numbers
+    #[derive(Debug, Clone)]
+    struct Counter {
+        value: u32,
+        sum: u64,
+    }
+
+    fn step(counter: &mut Counter) {
+        counter.value = if counter.value < 9 {
+            counter.value + 1
+        } else {
+            3
+        };
+        counter.sum += counter.value as u64;
+    }
+
+    fn fingerprint(counter: &Counter) -> u32 {
+        counter.value
+    }
+
+    const START: Counter = Counter { value: 0, sum: 0 };
+    const EXPECTED: Cycle = Cycle {
+        start: 3,
+        length: 7,
+    };
+
+    #[test]
+    fn test_find_cycle() {
+        assert_eq!(floyd(&START, step, fingerprint), EXPECTED);
+        assert_eq!(brent(&START, step, fingerprint), EXPECTED);
+        assert_eq!(find_with_hash(START, step, fingerprint), EXPECTED);
+
+        // A cycle that starts right away
+        let start = Counter { value: 5, sum: 0 };
+        let expected = Cycle {
+            start: 0,
+            length: 7,
+        };
+        assert_eq!(floyd(&start, step, fingerprint), expected);
+        assert_eq!(brent(&start, step, fingerprint), expected);
+        assert_eq!(find_with_hash(start, step, fingerprint), expected);
+    }
+
+    #[test]
+    fn test_equivalent_step() {
+        assert_eq!(EXPECTED.equivalent_step(2), 2);
+        assert_eq!(EXPECTED.equivalent_step(9), 9);
+        assert_eq!(EXPECTED.equivalent_step(10), 3);
+        assert_eq!(EXPECTED.equivalent_step(25), 4);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        for n in [0, 2, 3, 9, 10, 11, 100, 1234] {
+            let mut counter = START;
+            for _ in 0..n {
+                step(&mut counter);
+            }
+            let sum = extrapolate(START, n, step, fingerprint, |counter| counter.sum);
+            assert_eq!(sum, counter.sum, "after {n} steps");
+        }
+
+        let sum = extrapolate(START, 1_000_000_000_002, step, fingerprint, |c| c.sum);
+        assert_eq!(sum, 6 + 42 * 142_857_142_857);
+    }
+}
@@ -0,0 +1,279 @@
+use std::fmt;
+
+use num::{
+    traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub},
+    Rational64, Zero,
+};
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    fn apply(self, a: Rational64, b: Rational64) -> Result<Rational64> {
+        if self == Op::Div && b.is_zero() {
+            return Err(Error::new(format!("division by zero in {a} / {b}")));
+        }
+        match self {
+            Op::Add => a.checked_add(&b),
+            Op::Sub => a.checked_sub(&b),
+            Op::Mul => a.checked_mul(&b),
+            Op::Div => a.checked_div(&b),
+        }
+        .ok_or_else(|| Error::new(format!("arithmetic overflow in {a} {self} {b}")))
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Op::Add => "+",
+            Op::Sub => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+// An arithmetic expression in at most one unknown, evaluated with exact
+// rational arithmetic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Num(Rational64),
+    Unknown,
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn num(n: i64) -> Self {
+        Expr::Num(Rational64::from_integer(n))
+    }
+
+    pub fn binary(op: Op, lhs: Expr, rhs: Expr) -> Self {
+        Expr::Binary(op, Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn count_unknowns(&self) -> usize {
+        match self {
+            Expr::Num(_) => 0,
+            Expr::Unknown => 1,
+            Expr::Binary(_, lhs, rhs) => lhs.count_unknowns() + rhs.count_unknowns(),
+        }
+    }
+
+    pub fn eval(&self) -> Result<Rational64> {
+        match self {
+            Expr::Num(n) => Ok(*n),
+            Expr::Unknown => Err(Error::new("cannot evaluate an expression with an unknown")),
+            Expr::Binary(op, lhs, rhs) => op.apply(lhs.eval()?, rhs.eval()?),
+        }
+    }
+
+    // Folds every part of the expression that doesn't contain the unknown
+    // into a single number
+    pub fn simplify(&self) -> Result<Expr> {
+        match self {
+            Expr::Binary(op, lhs, rhs) => match (lhs.simplify()?, rhs.simplify()?) {
+                (Expr::Num(a), Expr::Num(b)) => Ok(Expr::Num(op.apply(a, b)?)),
+                (lhs, rhs) => Ok(Expr::binary(*op, lhs, rhs)),
+            },
+            _ => Ok(self.clone()),
+        }
+    }
+
+    pub fn to_linear(&self) -> Result<Linear> {
+        match self {
+            Expr::Num(n) => Ok(Linear::constant(*n)),
+            Expr::Unknown => Ok(Linear {
+                coefficient: Rational64::from_integer(1),
+                constant: Rational64::zero(),
+            }),
+            Expr::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.to_linear()?, rhs.to_linear()?);
+                match op {
+                    Op::Add => Ok(Linear {
+                        coefficient: Op::Add.apply(lhs.coefficient, rhs.coefficient)?,
+                        constant: Op::Add.apply(lhs.constant, rhs.constant)?,
+                    }),
+                    Op::Sub => Ok(Linear {
+                        coefficient: Op::Sub.apply(lhs.coefficient, rhs.coefficient)?,
+                        constant: Op::Sub.apply(lhs.constant, rhs.constant)?,
+                    }),
+                    Op::Mul => match (lhs.as_constant(), rhs.as_constant()) {
+                        (Some(factor), _) => rhs.scale(Op::Mul, factor),
+                        (_, Some(factor)) => lhs.scale(Op::Mul, factor),
+                        (None, None) => Err(Error::new(
+                            "the expression is not linear: it multiplies the unknown with itself",
+                        )),
+                    },
+                    Op::Div => match rhs.as_constant() {
+                        Some(divisor) => lhs.scale(Op::Div, divisor),
+                        None => Err(Error::new(
+                            "the expression is not linear: it divides by the unknown",
+                        )),
+                    },
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{n}"),
+            Expr::Unknown => write!(f, "x"),
+            Expr::Binary(op, lhs, rhs) => write!(f, "({lhs} {op} {rhs})"),
+        }
+    }
+}
+
+// coefficient * x + constant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Linear {
+    pub coefficient: Rational64,
+    pub constant: Rational64,
+}
+
+impl Linear {
+    fn constant(n: Rational64) -> Self {
+        Self {
+            coefficient: Rational64::zero(),
+            constant: n,
+        }
+    }
+
+    fn as_constant(&self) -> Option<Rational64> {
+        self.coefficient.is_zero().then_some(self.constant)
+    }
+
+    fn scale(&self, op: Op, factor: Rational64) -> Result<Self> {
+        Ok(Self {
+            coefficient: op.apply(self.coefficient, factor)?,
+            constant: op.apply(self.constant, factor)?,
+        })
+    }
+}
+
+// Returns the value of the unknown for which both sides are equal. The
+// unknown must appear exactly once.
+pub fn solve(lhs: &Expr, rhs: &Expr) -> Result<Rational64> {
+    match lhs.count_unknowns() + rhs.count_unknowns() {
+        0 => return Err(Error::new("the equation does not contain the unknown")),
+        1 => {}
+        n => {
+            return Err(Error::new(format!(
+                "the unknown appears {n} times, but it can only appear once"
+            )))
+        }
+    }
+
+    let lhs = lhs.simplify()?.to_linear()?;
+    let rhs = rhs.simplify()?.to_linear()?;
+    let coefficient = Op::Sub.apply(lhs.coefficient, rhs.coefficient)?;
+    let constant = Op::Sub.apply(rhs.constant, lhs.constant)?;
+
+    if coefficient.is_zero() {
+        return Err(if constant.is_zero() {
+            Error::new("the equation holds for every value of the unknown")
+        } else {
+            Error::new("the equation has no solution")
+        });
+    }
+    Op::Div.apply(constant, coefficient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x() -> Expr {
+        Expr::Unknown
+    }
+
+    fn rational(numer: i64, denom: i64) -> Rational64 {
+        Rational64::new(numer, denom)
+    }
+
+    #[test]
+    fn test_eval() {
+        // (4 + 2) / 4 - 1
+        let expr = Expr::binary(
+            Op::Sub,
+            Expr::binary(
+                Op::Div,
+                Expr::binary(Op::Add, Expr::num(4), Expr::num(2)),
+                Expr::num(4),
+            ),
+            Expr::num(1),
+        );
+        assert_eq!(expr.eval(), Ok(rational(1, 2)));
+        assert_eq!(expr.to_string(), "(((4 + 2) / 4) - 1)");
+
+        let expr = Expr::binary(Op::Div, Expr::num(1), Expr::num(0));
+        assert!(expr.eval().is_err());
+        let expr = Expr::binary(Op::Mul, Expr::num(i64::MAX), Expr::num(2));
+        assert!(expr.eval().is_err());
+        assert!(x().eval().is_err());
+    }
+
+    #[test]
+    fn test_simplify() {
+        let expr = Expr::binary(
+            Op::Add,
+            x(),
+            Expr::binary(Op::Mul, Expr::num(3), Expr::num(5)),
+        );
+        assert_eq!(
+            expr.simplify(),
+            Ok(Expr::binary(Op::Add, x(), Expr::num(15)))
+        );
+        assert_eq!(expr.count_unknowns(), 1);
+    }
+
+    #[test]
+    fn test_solve() {
+        // (x - 3) / 2 = 4
+        let lhs = Expr::binary(
+            Op::Div,
+            Expr::binary(Op::Sub, x(), Expr::num(3)),
+            Expr::num(2),
+        );
+        assert_eq!(solve(&lhs, &Expr::num(4)), Ok(rational(11, 1)));
+
+        // 10 - 3x = 5
+        let lhs = Expr::binary(
+            Op::Sub,
+            Expr::num(10),
+            Expr::binary(Op::Mul, Expr::num(3), x()),
+        );
+        assert_eq!(solve(&lhs, &Expr::num(5)), Ok(rational(5, 3)));
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let square = Expr::binary(Op::Mul, x(), x());
+        assert!(solve(&square, &Expr::num(4)).is_err());
+        assert!(square.to_linear().is_err());
+
+        let twice = Expr::binary(Op::Add, x(), x());
+        assert_eq!(
+            solve(&twice, &x()).unwrap_err().reason,
+            "the unknown appears 3 times, but it can only appear once"
+        );
+
+        let inverse = Expr::binary(Op::Div, Expr::num(1), x());
+        assert!(solve(&inverse, &Expr::num(4)).is_err());
+
+        assert!(solve(&Expr::num(1), &Expr::num(1)).is_err());
+        assert!(solve(&x(), &Expr::binary(Op::Add, x(), Expr::num(1))).is_err());
+        assert!(solve(&x(), &x()).is_err());
+    }
+}
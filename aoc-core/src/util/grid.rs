@@ -0,0 +1,300 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    error::{Error, Result},
+    util::Point,
+};
+
+// A dense 2D grid stored row by row, indexed with `Point<i32>` where `x` is
+// the column and `y` is the row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(Error::at_line(y + 1, "all rows must have the same width"));
+            }
+            cells.extend(row);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    // Parses one cell per character. `parse_cell` returns `None` for
+    // characters that aren't allowed.
+    pub fn parse(input: &str, mut parse_cell: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .map(|c| {
+                        parse_cell(c).ok_or_else(|| {
+                            Error::at_line(y + 1, format!("invalid character '{c}'"))
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point<i32>) -> bool {
+        0 <= p.x() && (p.x() as usize) < self.width && 0 <= p.y() && (p.y() as usize) < self.height
+    }
+
+    fn offset(&self, p: Point<i32>) -> Option<usize> {
+        self.contains(p)
+            .then(|| p.y() as usize * self.width + p.x() as usize)
+    }
+
+    pub fn get(&self, p: Point<i32>) -> Option<&T> {
+        self.offset(p).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, p: Point<i32>) -> Option<&mut T> {
+        self.offset(p).map(|offset| &mut self.cells[offset])
+    }
+
+    // All points of the grid, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point<i32>> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point<i32>, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point<i32>> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(p, _)| p)
+    }
+
+    // The up to four orthogonal neighbours of `p` that are inside the grid
+    pub fn manhattan_neighbors(&self, p: Point<i32>) -> impl Iterator<Item = Point<i32>> + '_ {
+        p.get_manhattan_neighbors().filter(|n| self.contains(*n))
+    }
+
+    // The up to eight neighbours of `p`, including diagonals, that are inside
+    // the grid
+    pub fn neighbors(&self, p: Point<i32>) -> impl Iterator<Item = Point<i32>> + '_ {
+        p.get_neighbors().filter(|n| self.contains(*n))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} is outside of the grid");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        Self {
+            width,
+            height,
+            cells: (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| f(x, y))
+                .collect(),
+        }
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.height, self.width, |x, y| {
+            self.cells[x * self.width + y].clone()
+        })
+    }
+
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.height, self.width, |x, y| {
+            self.cells[(self.height - 1 - x) * self.width + y].clone()
+        })
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.height, self.width, |x, y| {
+            self.cells[x * self.width + (self.width - 1 - y)].clone()
+        })
+    }
+}
+
+impl<T> Index<Point<i32>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point<i32>) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("({}, {}) is outside of the grid", p.x(), p.y()))
+    }
+}
+
+impl<T> IndexMut<Point<i32>> for Grid<T> {
+    fn index_mut(&mut self, p: Point<i32>) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("({}, {}) is outside of the grid", p.x(), p.y()))
+    }
+}
+
+impl<T> fmt::Display for Grid<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse("abc\ndef\n", Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], 'f');
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+
+        let err = Grid::parse("#.\n#x\n", |c| (c != 'x').then_some(c)).unwrap_err();
+        assert_eq!(err, Error::at_line(2, "invalid character 'x'"));
+
+        let err = Grid::parse("##\n#\n", Some).unwrap_err();
+        assert_eq!(err.line, Some(2));
+    }
+
+    #[test]
+    fn test_get() {
+        let mut grid = sample();
+        assert_eq!(grid.get(Point::new(0, 0)), Some(&'a'));
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 2)), None);
+
+        grid[Point::new(1, 0)] = 'x';
+        assert_eq!(grid.row(0), ['a', 'x', 'c']);
+        assert_eq!(grid.position(|c| *c == 'e'), Some(Point::new(1, 1)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_outside() {
+        let _ = sample()[Point::new(3, 1)];
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = sample();
+        assert_eq!(
+            grid.manhattan_neighbors(Point::new(0, 0))
+                .collect::<Vec<_>>(),
+            [Point::new(0, 1), Point::new(1, 0)]
+        );
+        assert_eq!(grid.neighbors(Point::new(1, 0)).count(), 5);
+        assert_eq!(grid.neighbors(Point::new(1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = sample();
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            [['a', 'b', 'c'], ['d', 'e', 'f']]
+        );
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.collect::<String>())
+                .collect::<Vec<_>>(),
+            ["ad", "be", "cf"]
+        );
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(
+            grid.rotate_clockwise().rotate_clockwise().to_string(),
+            "fed\ncba\n"
+        );
+        assert_eq!(
+            grid.map(|c| c.to_ascii_uppercase()).to_string(),
+            "ABC\nDEF\n"
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::ops::{Bound, Range, RangeBounds, RangeInclusive};
+
+use num::PrimInt;
+
+// A set of integers stored as sorted, disjoint inclusive ranges, so that the
+// largest value of `T` can be in the set. Ranges that overlap or touch are
+// merged when they are inserted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Converts any kind of range to an inclusive one, or `None` if it is empty
+fn to_inclusive<T: PrimInt>(range: impl RangeBounds<T>) -> Option<(T, T)> {
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start.checked_add(&T::one())?,
+        Bound::Unbounded => T::min_value(),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => *end,
+        Bound::Excluded(end) => end.checked_sub(&T::one())?,
+        Bound::Unbounded => T::max_value(),
+    };
+    (start <= end).then_some((start, end))
+}
+
+// The two's complement bits of the value, so that the difference of two
+// values can be computed for any `T` with a wrapping subtraction
+fn to_bits<T: PrimInt>(value: T) -> u128 {
+    value
+        .to_i128()
+        .map(|value| value as u128)
+        .or_else(|| value.to_u128())
+        .unwrap()
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        let Some((start, end)) = to_inclusive(range) else {
+            return;
+        };
+
+        // The ranges that end before `start - 1` and those that start after
+        // `end + 1` are kept as they are
+        let first = self
+            .ranges
+            .partition_point(|r| start > T::min_value() && r.1 < start - T::one());
+        let last = self
+            .ranges
+            .partition_point(|r| end == T::max_value() || r.0 <= end + T::one());
+        let merged = match self.ranges[first..last] {
+            [] => (start, end),
+            ref overlapping => (
+                start.min(overlapping[0].0),
+                end.max(overlapping[overlapping.len() - 1].1),
+            ),
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, range: impl RangeBounds<T>) {
+        let Some((start, end)) = to_inclusive(range) else {
+            return;
+        };
+
+        let first = self.ranges.partition_point(|r| r.1 < start);
+        let last = self.ranges.partition_point(|r| r.0 <= end);
+        if first == last {
+            return;
+        }
+
+        let mut remaining = Vec::new();
+        if self.ranges[first].0 < start {
+            remaining.push((self.ranges[first].0, start - T::one()));
+        }
+        if self.ranges[last - 1].1 > end {
+            remaining.push((end + T::one(), self.ranges[last - 1].1));
+        }
+        self.ranges.splice(first..last, remaining);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.1 < value);
+        self.ranges.get(i).is_some_and(|r| r.0 <= value)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // The number of values in the set. Only a set of all 128 bit integers
+    // has more than `u128::MAX`, in which case that is returned.
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().fold(0u128, |acc, (start, end)| {
+            let len = to_bits(*end)
+                .wrapping_sub(to_bits(*start))
+                .saturating_add(1);
+            acc.saturating_add(len)
+        })
+    }
+
+    // The ranges of the set, in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..=*end)
+    }
+
+    // The ranges within `range` that are not in the set
+    pub fn gaps(&self, range: impl RangeBounds<T>) -> impl Iterator<Item = RangeInclusive<T>> {
+        let mut gaps = IntervalSet::new();
+        gaps.insert(range);
+        gaps.difference(self)
+            .ranges
+            .into_iter()
+            .map(|(start, end)| start..=end)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for (start, end) in &other.ranges {
+            result.insert(*start..=*end);
+        }
+        result
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for (start, end) in &other.ranges {
+            result.remove(*start..=*end);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.difference(&self.difference(other))
+    }
+}
+
+impl<T: PrimInt> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|range| set.insert(range));
+        set
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|range| set.insert(range));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut set = IntervalSet::new();
+        set.insert(5..8);
+        set.insert(1..=2);
+        set.insert(10..12);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=2, 5..=7, 10..=11]);
+
+        // Touching ranges are merged
+        set.insert(3..5);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=7, 10..=11]);
+
+        set.insert(0..=10);
+        assert!(set.iter().eq(Some(0..=11)));
+
+        set.insert(4..4);
+        assert_eq!(set.len(), 12);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut set: IntervalSet<i64> = [0..10, 20..30].into_iter().collect();
+        set.remove(5..25);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..=4, 25..=29]);
+
+        set.remove(-3..=0);
+        set.remove(29..);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=4, 25..=28]);
+
+        set.remove(2..3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=1, 3..=4, 25..=28]);
+        assert_eq!(set.len(), 7);
+
+        set.remove(..);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_contains() {
+        let set: IntervalSet<u8> = [2..=4, 8..=8].into_iter().collect();
+        let contained = (0..10).filter(|i| set.contains(*i)).collect::<Vec<_>>();
+        assert_eq!(contained, [2, 3, 4, 8]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet<i32> = [0..10, 20..30].into_iter().collect();
+        let mut b = IntervalSet::new();
+        b.insert(5..25);
+
+        assert!(a.union(&b).iter().eq(Some(0..=29)));
+        assert_eq!(
+            a.intersection(&b).iter().collect::<Vec<_>>(),
+            [5..=9, 20..=24]
+        );
+        assert_eq!(
+            a.difference(&b).iter().collect::<Vec<_>>(),
+            [0..=4, 25..=29]
+        );
+        assert!(b.difference(&a).iter().eq(Some(10..=19)));
+    }
+
+    #[test]
+    fn test_gaps() {
+        let set: IntervalSet<i32> = [0..10, 20..30, 40..50].into_iter().collect();
+        assert_eq!(set.gaps(5..=45).collect::<Vec<_>>(), [10..=19, 30..=39]);
+        assert_eq!(
+            set.gaps(-5..60).collect::<Vec<_>>(),
+            [-5..=-1, 10..=19, 30..=39, 50..=59]
+        );
+        assert_eq!(set.gaps(0..10).count(), 0);
+    }
+
+    #[test]
+    fn test_type_limits() {
+        let mut set = IntervalSet::new();
+        set.insert(0..=u8::MAX);
+        assert!(set.contains(u8::MAX));
+        assert_eq!(set.len(), 256);
+        set.remove((Bound::Excluded(u8::MAX), Bound::Unbounded));
+        set.insert((Bound::Excluded(u8::MAX), Bound::Unbounded));
+        set.remove(..u8::MIN);
+        assert!(set.iter().eq(Some(0..=u8::MAX)));
+        set.remove(250..);
+        set.insert(..=3);
+        assert!(set.iter().eq(Some(0..=249)));
+
+        let mut set = IntervalSet::new();
+        set.insert(i32::MIN..i32::MAX);
+        assert_eq!(set.len(), u32::MAX as u128);
+        set.insert(i32::MAX..=i32::MAX);
+        assert_eq!(set.len(), 1 << 32);
+
+        let mut set = IntervalSet::new();
+        set.insert(..);
+        set.insert(5u128..10);
+        assert_eq!(set.len(), u128::MAX);
+    }
+}
@@ -0,0 +1,320 @@
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+// A cursor over the input text with a few combinators for the usual puzzle
+// input formats. Every method consumes what it recognizes, or fails with the
+// line and column of the first character that it couldn't make sense of.
+//
+// Spaces and tabs in front of a token are skipped, but line breaks have to be
+// consumed explicitly with `end_of_line`.
+#[derive(Debug, Clone)]
+pub struct Parser<'a> {
+    rest: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            rest: input,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    // An error at the current position
+    pub fn error(&self, reason: impl Into<String>) -> Error {
+        Error::at(self.line, self.column, reason)
+    }
+
+    fn expected(&self, what: &str) -> Error {
+        let found = match self.rest.chars().next() {
+            None => "the end of the input".to_owned(),
+            Some('\n' | '\r') => "the end of the line".to_owned(),
+            Some(_) => {
+                let token: String = self
+                    .rest
+                    .chars()
+                    .take_while(|c| !c.is_whitespace())
+                    .take(20)
+                    .collect();
+                format!("\"{token}\"")
+            }
+        };
+        self.error(format!("expected {what}, found {found}"))
+    }
+
+    fn advance(&mut self, len: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(len);
+        for c in taken.chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.rest = rest;
+        taken
+    }
+
+    // Skips spaces and tabs, but not line breaks
+    pub fn spaces(&mut self) {
+        let len = self.rest.len() - self.rest.trim_start_matches([' ', '\t']).len();
+        self.advance(len);
+    }
+
+    // Matches the words of the literal in order, with any amount of spaces
+    // before and between them. A word that ends in a letter or digit must
+    // not be followed by another one, so "Valve" doesn't match "Valves".
+    pub fn literal(&mut self, literal: &str) -> Result<()> {
+        for word in literal.split_whitespace() {
+            self.spaces();
+            let matched = self.rest.strip_prefix(word).is_some_and(|after| {
+                !word.ends_with(char::is_alphanumeric) || !after.starts_with(char::is_alphanumeric)
+            });
+            if !matched {
+                return Err(self.expected(&format!("\"{word}\"")));
+            }
+            self.advance(word.len());
+        }
+        Ok(())
+    }
+
+    // Like `literal`, but returns false and consumes nothing if the literal
+    // isn't there
+    pub fn try_literal(&mut self, literal: &str) -> bool {
+        let start = self.clone();
+        if self.literal(literal).is_ok() {
+            true
+        } else {
+            *self = start;
+            false
+        }
+    }
+
+    // Returns the value of the first literal that matches
+    pub fn one_of<T: Copy>(&mut self, options: &[(&str, T)]) -> Result<T> {
+        if let Some((_, value)) = options
+            .iter()
+            .find(|(literal, _)| self.try_literal(literal))
+        {
+            return Ok(*value);
+        }
+        let literals: Vec<String> = options
+            .iter()
+            .map(|(literal, _)| format!("\"{literal}\""))
+            .collect();
+        Err(self.expected(&format!("one of {}", literals.join(", "))))
+    }
+
+    // An integer with an optional sign
+    pub fn number<T: FromStr>(&mut self) -> Result<T> {
+        self.spaces();
+        let sign = usize::from(self.rest.starts_with(['+', '-']));
+        let digits = self.rest[sign..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        if digits == 0 {
+            return Err(self.expected("a number"));
+        }
+        let text = &self.rest[..sign + digits];
+        let number = text
+            .parse()
+            .map_err(|_| self.error(format!("invalid number \"{text}\"")))?;
+        self.advance(text.len());
+        Ok(number)
+    }
+
+    // A run of letters, digits and underscores
+    pub fn word(&mut self) -> Result<&'a str> {
+        self.spaces();
+        let len = self
+            .rest
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(self.rest.len());
+        if len == 0 {
+            return Err(self.expected("a word"));
+        }
+        Ok(self.advance(len))
+    }
+
+    // One or more items with the separator between them
+    pub fn separated<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut items = vec![item(self)?];
+        while self.try_literal(separator) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    // `key=value`, where the value is parsed by the given parser, e.g.
+    // `parser.key_value("x", Parser::number)`
+    pub fn key_value<T>(
+        &mut self,
+        key: &str,
+        value: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        self.literal(key)?;
+        self.literal("=")?;
+        value(self)
+    }
+
+    // Moves to the start of the next line. The last line of the input doesn't
+    // need a line break.
+    pub fn end_of_line(&mut self) -> Result<()> {
+        self.spaces();
+        let len = if self.rest.starts_with("\r\n") {
+            2
+        } else if self.rest.starts_with('\n') {
+            1
+        } else if self.rest.is_empty() {
+            0
+        } else {
+            return Err(self.expected("the end of the line"));
+        };
+        self.advance(len);
+        Ok(())
+    }
+
+    // Skips lines that contain nothing but spaces
+    pub fn blank_lines(&mut self) {
+        loop {
+            let start = self.clone();
+            self.spaces();
+            if self.rest.starts_with('\n') || self.rest.starts_with("\r\n") {
+                self.end_of_line().unwrap();
+            } else {
+                *self = start;
+                return;
+            }
+        }
+    }
+
+    // Parses every line of the rest of the input, and expects the line parser
+    // to consume the whole line
+    pub fn lines<T>(&mut self, mut line: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        while !self.is_empty() {
+            items.push(line(self)?);
+            self.end_of_line()?;
+        }
+        Ok(items)
+    }
+
+    // Expects that nothing but blank lines are left
+    pub fn end(&mut self) -> Result<()> {
+        self.blank_lines();
+        self.spaces();
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.expected("the end of the input"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens() -> Result<()> {
+        let mut parser = Parser::new("Valve AA has  flow rate=-13; x");
+        parser.literal("Valve")?;
+        assert_eq!(parser.word()?, "AA");
+        parser.literal("has flow")?;
+        assert_eq!(parser.key_value("rate", Parser::number::<i32>)?, -13);
+        assert_eq!(parser.one_of(&[(",", 0), (";", 1)])?, 1);
+        assert!(!parser.try_literal("y"));
+        assert_eq!(parser.word()?, "x");
+        parser.end()
+    }
+
+    #[test]
+    fn test_literal_whole_words() -> Result<()> {
+        let mut parser = Parser::new("Valves AA, x=1");
+        assert!(parser.literal("Valve").is_err());
+        assert!(parser.literal("Valvé").is_err());
+        assert!(!parser.try_literal("Valve"));
+        parser.literal("Valves")?;
+        assert_eq!(parser.word()?, "AA");
+        parser.literal(",")?;
+        assert_eq!(parser.key_value("x", Parser::number::<u8>)?, 1);
+        parser.end()
+    }
+
+    #[test]
+    fn test_separated_lines() -> Result<()> {
+        let mut parser = Parser::new("1, 2,3\n\n  \n4\n");
+        assert_eq!(parser.separated(",", Parser::number::<u8>)?, [1, 2, 3]);
+        parser.end_of_line()?;
+        parser.blank_lines();
+        assert_eq!(parser.lines(Parser::number::<u8>)?, [4]);
+        assert!(parser.is_empty());
+
+        let mut parser = Parser::new("1\n2\r\n3");
+        assert_eq!(parser.lines(Parser::number::<u8>)?, [1, 2, 3]);
+
+        // A lone carriage return is not a line break
+        let mut parser = Parser::new("1\rx");
+        assert_eq!(parser.number::<u8>()?, 1);
+        parser.blank_lines();
+        assert!(parser.end().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut parser = Parser::new("a=1\nb = x");
+        assert!(parser.key_value("a", Parser::number::<u8>).is_ok());
+        parser.end_of_line().unwrap();
+        assert_eq!(
+            parser.key_value("b", Parser::number::<u8>),
+            Err(Error::at(2, 5, "expected a number, found \"x\""))
+        );
+
+        let mut parser = Parser::new("300 y");
+        assert_eq!(
+            parser.number::<u8>(),
+            Err(Error::at(1, 1, "invalid number \"300\""))
+        );
+        assert_eq!(
+            parser.lines(Parser::number::<u16>),
+            Err(Error::at(1, 5, "expected the end of the line, found \"y\""))
+        );
+
+        let mut parser = Parser::new("*");
+        assert_eq!(
+            parser.one_of(&[("+", 0), ("-", 1)]),
+            Err(Error::at(1, 1, "expected one of \"+\", \"-\", found \"*\""))
+        );
+        assert_eq!(
+            Parser::new("").word(),
+            Err(Error::at(
+                1,
+                1,
+                "expected a word, found the end of the input"
+            ))
+        );
+    }
+}
@@ -0,0 +1,174 @@
+// A circular list whose elements can be moved by an offset in O(√n). Every
+// element keeps the id it was given on creation, its index in the initial
+// order, so that it can be found again after it has been moved.
+//
+// The ids are stored in buckets of about √n elements. Moving an element only
+// touches two buckets, and the buckets are evened out again after every √n
+// moves.
+#[derive(Debug, Clone)]
+pub struct Ring<T> {
+    values: Vec<T>,
+    buckets: Vec<Vec<usize>>,
+    bucket_of: Vec<usize>,
+    bucket_size: usize,
+    moves_since_rebuild: usize,
+}
+
+impl<T> Ring<T> {
+    pub fn new(values: Vec<T>) -> Self {
+        let bucket_size = (values.len() as f64).sqrt().ceil().max(1.0) as usize;
+        let mut ring = Self {
+            bucket_of: vec![0; values.len()],
+            values,
+            buckets: Vec::new(),
+            bucket_size,
+            moves_since_rebuild: 0,
+        };
+        ring.rebuild((0..ring.values.len()).collect());
+        ring
+    }
+
+    fn rebuild(&mut self, order: Vec<usize>) {
+        self.buckets = order
+            .chunks(self.bucket_size)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            for id in bucket {
+                self.bucket_of[*id] = i;
+            }
+        }
+        self.moves_since_rebuild = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    // The value of the element with the given id
+    pub fn value(&self, id: usize) -> &T {
+        &self.values[id]
+    }
+
+    // The current index of the element with the given id
+    pub fn index_of(&self, id: usize) -> usize {
+        let bucket = self.bucket_of[id];
+        let before: usize = self.buckets[..bucket].iter().map(Vec::len).sum();
+        before + self.buckets[bucket].iter().position(|i| *i == id).unwrap()
+    }
+
+    // The id of the element at the given index
+    pub fn id_at(&self, mut index: usize) -> Option<usize> {
+        for bucket in &self.buckets {
+            if index < bucket.len() {
+                return Some(bucket[index]);
+            }
+            index -= bucket.len();
+        }
+        None
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.id_at(index).map(|id| &self.values[id])
+    }
+
+    // Takes the element out of the ring and puts it back `offset` places
+    // further along, wrapping around in either direction
+    pub fn move_by(&mut self, id: usize, offset: i64) {
+        if self.len() < 2 {
+            return;
+        }
+
+        let index = self.index_of(id);
+        let bucket = self.bucket_of[id];
+        self.buckets[bucket].retain(|i| *i != id);
+
+        // Reducing the offset first keeps the sum from overflowing
+        let len = self.len() as i64 - 1;
+        let mut target = (index as i64 + offset.rem_euclid(len)).rem_euclid(len) as usize;
+        for (i, bucket) in self.buckets.iter_mut().enumerate() {
+            if target <= bucket.len() {
+                bucket.insert(target, id);
+                self.bucket_of[id] = i;
+                break;
+            }
+            target -= bucket.len();
+        }
+
+        self.moves_since_rebuild += 1;
+        if self.moves_since_rebuild >= self.bucket_size {
+            let order = self.buckets.concat();
+            self.rebuild(order);
+        }
+    }
+
+    // The ids in their current order
+    pub fn ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.buckets.iter().flatten().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.ids().map(|id| &self.values[id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The straightforward implementation that the ring is checked against
+    fn move_in_vec(v: &mut Vec<usize>, id: usize, offset: i64) {
+        let index = v.iter().position(|i| *i == id).unwrap();
+        v.remove(index);
+        let target = (index as i64 + offset).rem_euclid(v.len() as i64) as usize;
+        v.insert(target, id);
+    }
+
+    #[test]
+    fn test_move_by() {
+        let mut ring = Ring::new(vec!['a', 'b', 'c', 'd', 'e']);
+        ring.move_by(0, 1);
+        assert_eq!(ring.iter().collect::<String>(), "bacde");
+        ring.move_by(0, -2);
+        assert_eq!(ring.iter().collect::<String>(), "bcdae");
+        ring.move_by(4, 3);
+        assert_eq!(ring.iter().collect::<String>(), "bcdea");
+        ring.move_by(2, 8);
+        assert_eq!(ring.iter().collect::<String>(), "bcdea");
+        ring.move_by(1, i64::MAX);
+        ring.move_by(1, i64::MIN + 1);
+        assert_eq!(ring.iter().collect::<String>(), "bcdea");
+
+        assert_eq!(ring.index_of(3), 2);
+        assert_eq!(ring.id_at(4), Some(0));
+        assert_eq!(ring.get(2), Some(&'d'));
+        assert_eq!(ring.get(5), None);
+        assert_eq!(*ring.value(1), 'b');
+    }
+
+    #[test]
+    fn test_against_vec() {
+        let n = 100;
+        let mut ring = Ring::new((0..n).collect());
+        let mut v: Vec<usize> = (0..n).collect();
+
+        // Some pseudo-random offsets, including large and negative ones
+        let mut offset: i64 = 17;
+        for round in 0..5 {
+            for id in 0..n {
+                offset = (offset * 7919 + 104729) % 100_003 - 50_000;
+                ring.move_by(id, offset * (round + 1));
+                move_in_vec(&mut v, id, offset * (round + 1));
+            }
+            assert_eq!(ring.ids().collect::<Vec<_>>(), v);
+        }
+
+        for (index, id) in v.iter().enumerate() {
+            assert_eq!(ring.index_of(*id), index);
+        }
+    }
+}
@@ -0,0 +1,486 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use num::Zero;
+
+// How a search keeps track of the nodes it has already seen
+pub trait Visited<N> {
+    // Returns true if the node had not been visited before
+    fn insert(&mut self, node: &N) -> bool;
+    fn contains(&self, node: &N) -> bool;
+}
+
+impl<N> Visited<N> for HashSet<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn insert(&mut self, node: &N) -> bool {
+        HashSet::insert(self, node.clone())
+    }
+
+    fn contains(&self, node: &N) -> bool {
+        HashSet::contains(self, node)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    // Returns true if the bit was not set before
+    pub fn insert(&mut self, index: usize) -> bool {
+        let (word, mask) = (index / 64, 1 << (index % 64));
+        let is_new = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        is_new
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / 64)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+// Visited set for nodes that can be mapped to a small dense range of indices,
+// e.g. the cells of a grid. Much faster than hashing the nodes.
+pub struct DenseVisited<F> {
+    bits: BitSet,
+    index: F,
+}
+
+impl<F> DenseVisited<F> {
+    pub fn new(len: usize, index: F) -> Self {
+        Self {
+            bits: BitSet::new(len),
+            index,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits.count()
+    }
+}
+
+impl<N, F> Visited<N> for DenseVisited<F>
+where
+    F: Fn(&N) -> usize,
+{
+    fn insert(&mut self, node: &N) -> bool {
+        self.bits.insert((self.index)(node))
+    }
+
+    fn contains(&self, node: &N) -> bool {
+        self.bits.contains((self.index)(node))
+    }
+}
+
+struct Entry<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+    parent: Option<N>,
+}
+
+impl<N, C: PartialEq> PartialEq for Entry<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Eq> Eq for Entry<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Entry<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Entry<N, C> {
+    // Reversed, since `BinaryHeap` is a max-heap
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+// Searches over graphs given as a successor closure. The visited set and the
+// recorded paths are kept after a search, so that they can be inspected or
+// so that the search can be continued from other start nodes.
+pub struct Search<N, V = HashSet<N>> {
+    visited: V,
+    parents: Option<HashMap<N, N>>,
+    expanded: usize,
+}
+
+impl<N> Search<N>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self::with_visited(HashSet::new())
+    }
+}
+
+impl<N> Default for Search<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, V> Search<N, V>
+where
+    N: Clone + Eq + Hash,
+    V: Visited<N>,
+{
+    pub fn with_visited(visited: V) -> Self {
+        Self {
+            visited,
+            parents: None,
+            expanded: 0,
+        }
+    }
+
+    // Records the parent of each node so that `path_to` can be used
+    pub fn track_paths(mut self) -> Self {
+        self.parents = Some(HashMap::new());
+        self
+    }
+
+    pub fn visited(&self) -> &V {
+        &self.visited
+    }
+
+    // The number of nodes whose successors have been generated
+    pub fn expanded(&self) -> usize {
+        self.expanded
+    }
+
+    fn set_parent(&mut self, node: &N, parent: &N) {
+        if let Some(parents) = &mut self.parents {
+            parents.insert(node.clone(), parent.clone());
+        }
+    }
+
+    // Returns the path from the start node to `goal`, including both, if
+    // paths are tracked and `goal` has been reached
+    pub fn path_to(&self, goal: &N) -> Option<Vec<N>> {
+        let parents = self.parents.as_ref()?;
+        if !self.visited.contains(goal) {
+            return None;
+        }
+
+        let mut path = vec![goal.clone()];
+        while let Some(parent) = parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    // Returns the first goal that is found and the number of steps to it
+    pub fn bfs<I>(
+        &mut self,
+        start: N,
+        mut successors: impl FnMut(&N) -> I,
+        mut is_goal: impl FnMut(&N) -> bool,
+    ) -> Option<(N, usize)>
+    where
+        I: IntoIterator<Item = N>,
+    {
+        let mut queue = VecDeque::new();
+        self.visited.insert(&start);
+        queue.push_back((start, 0));
+
+        while let Some((node, steps)) = queue.pop_front() {
+            if is_goal(&node) {
+                return Some((node, steps));
+            }
+
+            self.expanded += 1;
+            for next in successors(&node) {
+                if self.visited.insert(&next) {
+                    self.set_parent(&next, &node);
+                    queue.push_back((next, steps + 1));
+                }
+            }
+        }
+        None
+    }
+
+    pub fn dfs<I>(
+        &mut self,
+        start: N,
+        mut successors: impl FnMut(&N) -> I,
+        mut is_goal: impl FnMut(&N) -> bool,
+    ) -> Option<N>
+    where
+        I: IntoIterator<Item = N>,
+    {
+        let mut stack = Vec::new();
+        self.visited.insert(&start);
+        stack.push(start);
+
+        while let Some(node) = stack.pop() {
+            if is_goal(&node) {
+                return Some(node);
+            }
+
+            self.expanded += 1;
+            for next in successors(&node) {
+                if self.visited.insert(&next) {
+                    self.set_parent(&next, &node);
+                    stack.push(next);
+                }
+            }
+        }
+        None
+    }
+
+    // Visits every node reachable from `starts` and returns how many new
+    // nodes were visited
+    pub fn flood_fill<I>(
+        &mut self,
+        starts: impl IntoIterator<Item = N>,
+        mut successors: impl FnMut(&N) -> I,
+    ) -> usize
+    where
+        I: IntoIterator<Item = N>,
+    {
+        let mut queue: VecDeque<N> = starts
+            .into_iter()
+            .filter(|start| self.visited.insert(start))
+            .collect();
+        let mut count = queue.len();
+
+        while let Some(node) = queue.pop_front() {
+            self.expanded += 1;
+            for next in successors(&node) {
+                if self.visited.insert(&next) {
+                    self.set_parent(&next, &node);
+                    queue.push_back(next);
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    // Returns the first goal that is found and the lowest cost to it
+    pub fn dijkstra<C, I>(
+        &mut self,
+        start: N,
+        successors: impl FnMut(&N) -> I,
+        is_goal: impl FnMut(&N) -> bool,
+    ) -> Option<(N, C)>
+    where
+        C: Copy + Ord + Add<Output = C> + Zero,
+        I: IntoIterator<Item = (N, C)>,
+    {
+        self.astar(start, successors, |_| C::zero(), is_goal)
+    }
+
+    // Like `dijkstra`, but explores the nodes in order of cost plus
+    // `heuristic`. Nodes are closed when they are expanded and never reopened,
+    // so for the returned cost to be the lowest the heuristic must be
+    // consistent: it may not drop by more than the cost of any step, and it
+    // must be zero at the goal.
+    pub fn astar<C, I>(
+        &mut self,
+        start: N,
+        mut successors: impl FnMut(&N) -> I,
+        mut heuristic: impl FnMut(&N) -> C,
+        mut is_goal: impl FnMut(&N) -> bool,
+    ) -> Option<(N, C)>
+    where
+        C: Copy + Ord + Add<Output = C> + Zero,
+        I: IntoIterator<Item = (N, C)>,
+    {
+        let mut heap = BinaryHeap::new();
+        heap.push(Entry {
+            priority: heuristic(&start),
+            cost: C::zero(),
+            node: start,
+            parent: None,
+        });
+
+        while let Some(Entry {
+            cost, node, parent, ..
+        }) = heap.pop()
+        {
+            if !self.visited.insert(&node) {
+                continue;
+            }
+            if let Some(parent) = parent {
+                self.set_parent(&node, &parent);
+            }
+            if is_goal(&node) {
+                return Some((node, cost));
+            }
+
+            self.expanded += 1;
+            for (next, step_cost) in successors(&node) {
+                if !self.visited.contains(&next) {
+                    let cost = cost + step_cost;
+                    heap.push(Entry {
+                        priority: cost + heuristic(&next),
+                        cost,
+                        node: next,
+                        parent: Some(node.clone()),
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Grid, Point};
+
+    fn maze() -> Grid<bool> {
+        let input = "..#....\n.##.##.\n.#..#..\n...#.##\n#...#.#\n";
+        Grid::parse(input, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    fn open_neighbors(grid: &Grid<bool>, p: Point<i32>) -> Vec<Point<i32>> {
+        grid.manhattan_neighbors(p).filter(|n| !grid[*n]).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = maze();
+        let goal = Point::new(6, 0);
+        let mut search = Search::new().track_paths();
+
+        let found = search.bfs(
+            Point::new(0, 0),
+            |p| open_neighbors(&grid, *p),
+            |p| *p == goal,
+        );
+        assert_eq!(found, Some((goal, 12)));
+
+        let path = search.path_to(&goal).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!(path[0], Point::new(0, 0));
+        assert!(path
+            .windows(2)
+            .all(|pair| pair[0].manhattan_distance_to(&pair[1]) == 1 && !grid[pair[1]]));
+        assert!(search.expanded() > 0);
+
+        let mut search = Search::new();
+        let found = search.bfs(
+            Point::new(0, 0),
+            |p| open_neighbors(&grid, *p),
+            |p| *p == Point::new(5, 4),
+        );
+        assert_eq!(found, None);
+        assert_eq!(search.path_to(&goal), None);
+    }
+
+    #[test]
+    fn test_dfs() {
+        let grid = maze();
+        let goal = Point::new(6, 0);
+        let mut search = Search::new().track_paths();
+
+        let found = search.dfs(
+            Point::new(0, 0),
+            |p| open_neighbors(&grid, *p),
+            |p| *p == goal,
+        );
+        assert_eq!(found, Some(goal));
+        assert!(search.path_to(&goal).unwrap().len() >= 13);
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = maze();
+        let index = |p: &Point<i32>| p.y() as usize * grid.width() + p.x() as usize;
+        let mut search = Search::with_visited(DenseVisited::new(35, index));
+
+        let count = search.flood_fill([Point::new(0, 0)], |p| open_neighbors(&grid, *p));
+        assert_eq!(count, 20);
+        assert_eq!(search.visited().count(), 20);
+        assert!(!search.visited().contains(&Point::new(5, 4)));
+        assert_eq!(search.expanded(), 20);
+
+        // Already visited nodes are not counted again
+        let count = search.flood_fill([Point::new(0, 0), Point::new(5, 4)], |p| {
+            open_neighbors(&grid, *p)
+        });
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        // Moving down is three times as expensive as moving in any other way
+        let grid = maze();
+        let successors = |p: &Point<i32>| {
+            open_neighbors(&grid, *p)
+                .into_iter()
+                .map(|n| (n, if n.y() > p.y() { 3 } else { 1 }))
+                .collect::<Vec<_>>()
+        };
+        let start = Point::new(3, 0);
+        let goal = Point::new(1, 4);
+
+        let mut search = Search::new().track_paths();
+        assert_eq!(
+            search.dijkstra(start, successors, |p| *p == goal),
+            Some((goal, 14))
+        );
+        let dijkstra_expanded = search.expanded();
+        assert_eq!(search.path_to(&goal).unwrap().first(), Some(&start));
+
+        let mut search = Search::new();
+        let found = search.astar(
+            start,
+            successors,
+            |p| p.manhattan_distance_to(&goal),
+            |p| *p == goal,
+        );
+        assert_eq!(found, Some((goal, 14)));
+        assert!(search.expanded() <= dijkstra_expanded);
+    }
+
+    #[test]
+    fn test_bitset() {
+        let mut bits = BitSet::new(130);
+        assert!(bits.insert(129));
+        assert!(!bits.insert(129));
+        assert!(bits.insert(3));
+        assert!(bits.contains(3));
+        assert!(!bits.contains(4));
+        assert!(!bits.contains(1000));
+        assert_eq!(bits.count(), 2);
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    array,
+    hash::{Hash, Hasher},
+    ops::{Add, AddAssign, Index, IndexMut, Mul, Sub, SubAssign},
+    str::FromStr,
+};
+
+use num::{One, Zero};
+
+// A point or offset in N dimensions. The coordinates are stored in axis order,
+// so x is at index 0, y at 1 and z at 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vector<T, const N: usize>(pub [T; N]);
+
+pub type Point<T> = Vector<T, 2>;
+
+// Arrays also hash their length, which slows down the hash sets of points
+impl<T: Hash, const N: usize> Hash for Vector<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in &self.0 {
+            c.hash(state);
+        }
+    }
+}
+
+impl<T: Copy> Vector<T, 2> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self([x, y])
+    }
+
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+}
+
+impl<T: Copy> Vector<T, 3> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self([x, y, z])
+    }
+
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.0[2]
+    }
+}
+
+impl<T, const N: usize> Vector<T, N> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Vector<U, N> {
+        Vector(self.0.map(f))
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Vector<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &T {
+        &self.0[axis]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Vector<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut T {
+        &mut self.0[axis]
+    }
+}
+
+impl<T, const N: usize> Add for Vector<T, N>
+where
+    T: Add<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+}
+
+impl<T, const N: usize> AddAssign for Vector<T, N>
+where
+    T: Add<Output = T> + Copy,
+{
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T, const N: usize> Sub for Vector<T, N>
+where
+    T: Sub<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(array::from_fn(|i| self.0[i] - other.0[i]))
+    }
+}
+
+impl<T, const N: usize> SubAssign for Vector<T, N>
+where
+    T: Sub<Output = T> + Copy,
+{
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T, const N: usize> Mul<T> for Vector<T, N>
+where
+    T: Mul<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        self.map(|c| c * factor)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseVectorError;
+
+// Parses comma separated coordinates, e.g. `1,-2,3`
+impl<T, const N: usize> FromStr for Vector<T, N>
+where
+    T: FromStr,
+{
+    type Err = ParseVectorError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let coordinates = s
+            .split(',')
+            .map(|c| c.trim().parse::<T>().map_err(|_| ParseVectorError))
+            .collect::<std::result::Result<Vec<T>, _>>()?;
+        Ok(Self(coordinates.try_into().map_err(|_| ParseVectorError)?))
+    }
+}
+
+// The distance between two coordinates, subtracting the smaller one so that
+// unsigned coordinates work too
+fn abs_diff<T: Sub<Output = T> + PartialOrd>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T, const N: usize> Vector<T, N>
+where
+    T: Add<Output = T> + Sub<Output = T> + Zero + PartialOrd + Copy,
+{
+    pub fn manhattan_distance_to(&self, other: &Self) -> T {
+        (0..N).fold(T::zero(), |sum, i| sum + abs_diff(self.0[i], other.0[i]))
+    }
+
+    // The number of king moves between the points
+    pub fn chebyshev_distance_to(&self, other: &Self) -> T {
+        (0..N).fold(T::zero(), |max, i| {
+            let distance = abs_diff(self.0[i], other.0[i]);
+            if distance > max {
+                distance
+            } else {
+                max
+            }
+        })
+    }
+}
+
+impl<T, const N: usize> Vector<T, N>
+where
+    T: PartialOrd,
+{
+    // Whether the point is in the box from `lower` to `upper`, excluding
+    // `upper`
+    pub fn is_within(&self, lower: &Self, upper: &Self) -> bool {
+        (0..N).all(|i| lower.0[i] <= self.0[i] && self.0[i] < upper.0[i])
+    }
+}
+
+impl<T, const N: usize> Vector<T, N>
+where
+    T: Add<Output = T> + Sub<Output = T> + One + Copy,
+{
+    // The 2N points that differ by one along a single axis. For N = 2 they
+    // are up, left, down and right, with y pointing down.
+    pub fn get_manhattan_neighbors(&self) -> impl Iterator<Item = Self> {
+        let center = *self;
+        [false, true].into_iter().flat_map(move |increase| {
+            (0..N).rev().map(move |axis| {
+                let mut neighbor = center;
+                neighbor.0[axis] = if increase {
+                    center.0[axis] + T::one()
+                } else {
+                    center.0[axis] - T::one()
+                };
+                neighbor
+            })
+        })
+    }
+
+    // The 3^N - 1 points around this one, ordered by their last coordinate
+    // first, so in reading order for N = 2
+    pub fn get_neighbors(&self) -> impl Iterator<Item = Self> {
+        let center = *self;
+        let count = 3usize.pow(N as u32);
+        (0..count)
+            .filter(move |&offsets| offsets != count / 2)
+            .map(move |mut offsets| {
+                let mut neighbor = center;
+                for axis in 0..N {
+                    match offsets % 3 {
+                        0 => neighbor.0[axis] = center.0[axis] - T::one(),
+                        2 => neighbor.0[axis] = center.0[axis] + T::one(),
+                        _ => {}
+                    }
+                    offsets /= 3;
+                }
+                neighbor
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ops() {
+        let a = Point::new(1, 2);
+        let b = Point::new(-3, 5);
+        assert_eq!(a + b, Point::new(-2, 7));
+        assert_eq!(a - b, Point::new(4, -3));
+        assert_eq!(b * 2, Point::new(-6, 10));
+        let mut c = Vector::<i64, 3>::new(1, 2, 3);
+        c += Vector([1, 1, 1]);
+        c -= Vector([0, 0, 2]);
+        c[1] = 7;
+        assert_eq!((c.x(), c.y(), c.z()), (2, 7, 2));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Vector([1, -2, 3, 0]);
+        let b = Vector([4, 2, 3, -1]);
+        assert_eq!(a.manhattan_distance_to(&b), 8);
+        assert_eq!(a.chebyshev_distance_to(&b), 4);
+        assert!(Point::new(0, 4).is_within(&Point::new(0, 0), &Point::new(5, 5)));
+        assert!(!Point::new(5, 4).is_within(&Point::new(0, 0), &Point::new(5, 5)));
+
+        let a = Point::<u8>::new(1, 200);
+        let b = Point::new(7, 190);
+        assert_eq!(a.manhattan_distance_to(&b), 16);
+        assert_eq!(b.chebyshev_distance_to(&a), 10);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p = Point::new(0, 0);
+        assert_eq!(
+            p.get_manhattan_neighbors().collect::<Vec<_>>(),
+            [
+                Point::new(0, -1),
+                Point::new(-1, 0),
+                Point::new(0, 1),
+                Point::new(1, 0)
+            ]
+        );
+        let neighbors: Vec<_> = p.get_neighbors().collect();
+        assert_eq!(neighbors.len(), 8);
+        assert_eq!(neighbors[0], Point::new(-1, -1));
+        assert_eq!(neighbors[3], Point::new(-1, 0));
+        assert_eq!(neighbors[4], Point::new(1, 0));
+
+        let cube = Vector([1, 1, 1]);
+        assert_eq!(cube.get_manhattan_neighbors().count(), 6);
+        assert_eq!(cube.get_neighbors().count(), 26);
+        assert!(cube
+            .get_neighbors()
+            .all(|n| n != cube && n.chebyshev_distance_to(&cube) == 1));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("3, -4".parse(), Ok(Point::new(3, -4)));
+        assert_eq!("1,2,3".parse(), Ok(Vector([1u8, 2, 3])));
+        assert_eq!("1,2".parse::<Vector<u8, 3>>(), Err(ParseVectorError));
+        assert_eq!("1,2,3".parse::<Point<u8>>(), Err(ParseVectorError));
+        assert_eq!("1,a".parse::<Point<u8>>(), Err(ParseVectorError));
+    }
+}
@@ -0,0 +1,251 @@
+use std::array;
+
+use crate::{
+    error::{Error, Result},
+    util::{
+        search::{BitSet, DenseVisited, Search},
+        Vector,
+    },
+};
+
+pub type Voxel = Vector<i32, 3>;
+
+// The largest box that a set is stored in, which takes 32 MiB
+const MAX_BITS: u64 = 1 << 28;
+
+// A set of voxels stored as one bit per voxel of their bounding box. The box
+// is one voxel larger than the voxels on every side, so that the outside of a
+// shape is connected all around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Voxels {
+    // The corner of the box with the smallest coordinates
+    min: Voxel,
+    size: [usize; 3],
+    bits: BitSet,
+    len: usize,
+}
+
+impl Voxels {
+    // Fails if the voxels are spread out too far to store their box
+    pub fn new(voxels: &[Voxel]) -> Result<Self> {
+        let first = voxels.first().copied().unwrap_or(Voxel::new(0, 0, 0));
+        let (min, max) = voxels.iter().fold((first, first), |(min, max), v| {
+            (
+                Vector(array::from_fn(|axis| min[axis].min(v[axis]))),
+                Vector(array::from_fn(|axis| max[axis].max(v[axis]))),
+            )
+        });
+
+        let too_large = || Error::new("the voxels are spread out too far");
+        let mut padded_min = min;
+        let mut size = [0; 3];
+        for axis in 0..3 {
+            padded_min[axis] = min[axis].checked_sub(1).ok_or_else(too_large)?;
+            let padded_max = max[axis].checked_add(1).ok_or_else(too_large)?;
+            size[axis] = usize::try_from(i64::from(padded_max) - i64::from(padded_min[axis]) + 1)
+                .map_err(|_| too_large())?;
+        }
+        size.iter()
+            .try_fold(1u64, |product, len| product.checked_mul(*len as u64))
+            .filter(|bits| *bits <= MAX_BITS)
+            .ok_or_else(too_large)?;
+
+        let mut grid = Self::empty(padded_min, size);
+        for voxel in voxels {
+            grid.insert(voxel);
+        }
+        Ok(grid)
+    }
+
+    // A set without any voxels, in the box of `size` from `min`
+    fn empty(min: Voxel, size: [usize; 3]) -> Self {
+        Self {
+            min,
+            size,
+            bits: BitSet::new(size.iter().product()),
+            len: 0,
+        }
+    }
+
+    fn index(&self, voxel: &Voxel) -> Option<usize> {
+        (0..3).try_fold(0, |index, axis| {
+            let offset = usize::try_from(i64::from(voxel[axis]) - i64::from(self.min[axis]))
+                .ok()
+                .filter(|offset| *offset < self.size[axis])?;
+            Some(index * self.size[axis] + offset)
+        })
+    }
+
+    fn voxel(&self, mut index: usize) -> Voxel {
+        let mut voxel = self.min;
+        for axis in (0..3).rev() {
+            voxel[axis] += (index % self.size[axis]) as i32;
+            index /= self.size[axis];
+        }
+        voxel
+    }
+
+    fn insert(&mut self, voxel: &Voxel) {
+        let index = self.index(voxel).unwrap();
+        if self.bits.insert(index) {
+            self.len += 1;
+        }
+    }
+
+    pub fn contains(&self, voxel: &Voxel) -> bool {
+        self.index(voxel)
+            .is_some_and(|index| self.bits.contains(index))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Voxel> + '_ {
+        (0..self.bits_len())
+            .filter(|index| self.bits.contains(*index))
+            .map(|index| self.voxel(index))
+    }
+
+    // The neighbours that share a face with the voxel and are in the box
+    fn face_neighbors<'a>(&'a self, voxel: &Voxel) -> impl Iterator<Item = Voxel> + 'a {
+        voxel
+            .get_manhattan_neighbors()
+            .filter(|neighbor| self.index(neighbor).is_some())
+    }
+
+    // The number of faces that don't touch another voxel, including those of
+    // air pockets inside the shape
+    pub fn surface_area(&self) -> usize {
+        self.iter()
+            .flat_map(|voxel| voxel.get_manhattan_neighbors())
+            .filter(|neighbor| !self.contains(neighbor))
+            .count()
+    }
+
+    // The empty voxels that can be reached from outside of the shape
+    pub fn exterior(&self) -> Self {
+        let mut exterior = Self::empty(self.min, self.size);
+        let mut search = Search::with_visited(self.visited());
+        search.flood_fill([self.min], |voxel| {
+            exterior.insert(voxel);
+            self.face_neighbors(voxel)
+                .filter(|neighbor| !self.contains(neighbor))
+        });
+        exterior
+    }
+
+    // The number of faces that can be reached from outside of the shape
+    pub fn exterior_surface_area(&self) -> usize {
+        let exterior = self.exterior();
+        self.iter()
+            .flat_map(|voxel| voxel.get_manhattan_neighbors())
+            .filter(|neighbor| exterior.contains(neighbor))
+            .count()
+    }
+
+    // Groups the voxels into the shapes that they form, where voxels are
+    // connected if they share a face
+    pub fn components(&self) -> Vec<Vec<Voxel>> {
+        let mut search = Search::with_visited(self.visited());
+        let mut components = Vec::new();
+        for voxel in self.iter() {
+            let mut component = Vec::new();
+            search.flood_fill([voxel], |voxel| {
+                component.push(*voxel);
+                self.face_neighbors(voxel)
+                    .filter(|neighbor| self.contains(neighbor))
+            });
+            if !component.is_empty() {
+                components.push(component);
+            }
+        }
+        components
+    }
+
+    // Visited set for searches through the box
+    fn visited(&self) -> DenseVisited<impl Fn(&Voxel) -> usize + '_> {
+        DenseVisited::new(self.bits_len(), |voxel: &Voxel| self.index(voxel).unwrap())
+    }
+
+    fn bits_len(&self) -> usize {
+        self.size.iter().product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 3x3x3 cube without its center
+    fn hollow_cube() -> Vec<Voxel> {
+        (0..27)
+            .map(|i| Voxel::new(i % 3, i / 3 % 3, i / 9))
+            .filter(|v| *v != Voxel::new(1, 1, 1))
+            .collect()
+    }
+
+    #[test]
+    fn test_contains() {
+        let voxels = Voxels::new(&[Voxel::new(-5, 0, 30), Voxel::new(2, -1, 3)]).unwrap();
+        assert_eq!(voxels.len(), 2);
+        assert!(voxels.contains(&Voxel::new(-5, 0, 30)));
+        assert!(voxels.contains(&Voxel::new(2, -1, 3)));
+        assert!(!voxels.contains(&Voxel::new(2, -1, 4)));
+        assert!(!voxels.contains(&Voxel::new(100, 100, 100)));
+        assert!(!voxels.contains(&Voxel::new(i32::MAX, i32::MIN, 0)));
+        assert_eq!(
+            voxels.iter().collect::<Vec<_>>(),
+            [Voxel::new(-5, 0, 30), Voxel::new(2, -1, 3)]
+        );
+        assert!(Voxels::new(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_too_large() {
+        let far_apart = [Voxel::new(0, 0, 0), Voxel::new(100000, 100000, 100000)];
+        assert!(Voxels::new(&far_apart).is_err());
+        assert!(Voxels::new(&[Voxel::new(i32::MAX, 0, 0)]).is_err());
+        assert!(Voxels::new(&[Voxel::new(0, i32::MIN, 0)]).is_err());
+        let corners = [
+            Voxel::new(i32::MIN + 1, 0, 0),
+            Voxel::new(i32::MAX - 1, 0, 0),
+        ];
+        assert!(Voxels::new(&corners).is_err());
+    }
+
+    #[test]
+    fn test_surface_area() {
+        let pair = Voxels::new(&[Voxel::new(1, 1, 1), Voxel::new(2, 1, 1)]).unwrap();
+        assert_eq!(pair.surface_area(), 10);
+        assert_eq!(pair.exterior_surface_area(), 10);
+
+        let hollow = Voxels::new(&hollow_cube()).unwrap();
+        assert_eq!(hollow.surface_area(), 60);
+        assert_eq!(hollow.exterior_surface_area(), 54);
+        assert!(!hollow.exterior().contains(&Voxel::new(1, 1, 1)));
+        assert!(hollow.exterior().contains(&Voxel::new(-1, -1, -1)));
+    }
+
+    #[test]
+    fn test_components() {
+        let mut voxels = hollow_cube();
+        voxels.extend([
+            Voxel::new(5, 5, 5),
+            Voxel::new(5, 5, 6),
+            Voxel::new(6, 6, 6),
+        ]);
+        let mut sizes: Vec<usize> = Voxels::new(&voxels)
+            .unwrap()
+            .components()
+            .iter()
+            .map(Vec::len)
+            .collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, [1, 2, 26]);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    io::{self, Stdout, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::util::Grid;
+
+mod render;
+
+pub use render::Recorder;
+
+pub type Rgb = [u8; 3];
+
+// The colors that the cells of a day's frames are drawn with in images
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    // Fills the space around frames that are smaller than the image
+    pub background: Rgb,
+    pub colors: &'static [(char, Rgb)],
+}
+
+impl Palette {
+    pub const DEFAULT: Palette = Palette {
+        background: [16, 16, 32],
+        colors: &[
+            ('.', [16, 16, 32]),
+            (' ', [16, 16, 32]),
+            ('#', [200, 200, 200]),
+        ],
+    };
+
+    // Characters without a color get a fixed one derived from the character
+    pub fn color(&self, c: char) -> Rgb {
+        match self.colors.iter().find(|(other, _)| *other == c) {
+            Some((_, color)) => *color,
+            None => {
+                let hash = (c as u32).wrapping_mul(2654435761).to_be_bytes();
+                [hash[0] | 0x40, hash[1] | 0x40, hash[2] | 0x40]
+            }
+        }
+    }
+}
+
+// Receives the frames of a simulation while a day is being solved
+pub trait Observer {
+    // Whether any frames are shown at all, so that solvers can skip work that
+    // is only needed to draw them
+    fn is_active(&self) -> bool {
+        true
+    }
+
+    // Called before every frame. Returns whether the frame will be shown, so
+    // that solvers only draw the frames that are actually needed.
+    fn wants_frame(&mut self) -> bool;
+
+    fn frame(&mut self, frame: &Grid<char>);
+}
+
+impl dyn Observer + '_ {
+    // Draws the next frame with `draw` if it is wanted
+    pub fn show(&mut self, draw: impl FnOnce() -> Grid<char>) {
+        if self.wants_frame() {
+            self.frame(&draw());
+        }
+    }
+}
+
+// Ignores all frames, for solving without a visualization
+pub struct NoObserver;
+
+impl Observer for NoObserver {
+    fn is_active(&self) -> bool {
+        false
+    }
+
+    fn wants_frame(&mut self) -> bool {
+        false
+    }
+
+    fn frame(&mut self, _frame: &Grid<char>) {}
+}
+
+// Replays the frames in the terminal, redrawing the screen for each one
+pub struct Terminal<W = Stdout> {
+    out: W,
+    frame_time: Duration,
+    skip: usize,
+    num_frames: usize,
+    last_frame: Option<Instant>,
+}
+
+impl Terminal {
+    // Shows every `skip`th frame, at most `fps` of them per second
+    pub fn new(fps: f64, skip: usize) -> Self {
+        Self::with_writer(io::stdout(), fps, skip)
+    }
+}
+
+impl<W: Write> Terminal<W> {
+    pub fn with_writer(out: W, fps: f64, skip: usize) -> Self {
+        assert!(fps > 0.0 && skip > 0);
+        Self {
+            out,
+            frame_time: Duration::from_secs_f64(1.0 / fps),
+            skip,
+            num_frames: 0,
+            last_frame: None,
+        }
+    }
+}
+
+impl<W: Write> Observer for Terminal<W> {
+    fn wants_frame(&mut self) -> bool {
+        let wanted = self.num_frames.is_multiple_of(self.skip);
+        self.num_frames += 1;
+        wanted
+    }
+
+    fn frame(&mut self, frame: &Grid<char>) {
+        if let Some(elapsed) = self.last_frame.map(|last| last.elapsed()) {
+            thread::sleep(self.frame_time.saturating_sub(elapsed));
+        }
+        self.last_frame = Some(Instant::now());
+
+        // Moves the cursor to the top left corner and clears the screen. The
+        // replay is best effort, so write errors are ignored.
+        let _ = write!(self.out, "\x1b[H\x1b[2J{frame}");
+        let _ = self.out.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal() {
+        let mut out = Vec::new();
+        let mut terminal = Terminal::with_writer(&mut out, 1000.0, 2);
+        let observer: &mut dyn Observer = &mut terminal;
+
+        for i in 0..5 {
+            observer.show(|| Grid::new(i + 1, 1, '#'));
+        }
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[H\x1b[2J#\n\x1b[H\x1b[2J###\n\x1b[H\x1b[2J#####\n"
+        );
+    }
+
+    #[test]
+    fn test_palette() {
+        const PALETTE: Palette = Palette {
+            background: [0, 0, 0],
+            colors: &[('#', [255, 0, 0])],
+        };
+        assert_eq!(PALETTE.color('#'), [255, 0, 0]);
+        assert_eq!(PALETTE.color('x'), PALETTE.color('x'));
+        assert_ne!(PALETTE.color('x'), PALETTE.color('y'));
+    }
+
+    #[test]
+    fn test_no_observer() {
+        let observer: &mut dyn Observer = &mut NoObserver;
+        observer.show(|| panic!("no frames should be drawn"));
+    }
+}
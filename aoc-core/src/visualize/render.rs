@@ -0,0 +1,385 @@
+use std::{
+    array,
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use weezl::BitOrder;
+
+use super::{Observer, Palette, Rgb};
+use crate::{
+    error::{Error, Result},
+    util::{Grid, Point},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Gif,
+    Png,
+}
+
+// Records frames as images, either as one animated GIF or as a sequence of
+// numbered PNG files next to the given path, depending on its extension
+pub struct Recorder {
+    path: PathBuf,
+    format: Format,
+    palette: Palette,
+    // The width and height of a cell in pixels
+    scale: usize,
+    // In hundredths of a second, as GIF stores it
+    frame_delay: u16,
+    skip: usize,
+    num_frames: usize,
+    // GIF frames are spooled to a file next to the output until the end,
+    // because the size of the animation has to be written before the first
+    // frame and is only known once every frame has been seen. Each one is
+    // stored as its width and height, followed by its LZW compressed indices
+    // into `colors`.
+    spool: Option<BufWriter<File>>,
+    // The size of the largest frame so far, in cells
+    max_size: (usize, usize),
+    colors: Vec<Rgb>,
+    color_indices: HashMap<char, u8>,
+    num_written: usize,
+    // The first error while writing a frame, reported by `finish`
+    error: Option<Error>,
+}
+
+impl Recorder {
+    pub fn new(path: &Path, palette: Palette, fps: f64, skip: usize, scale: usize) -> Result<Self> {
+        assert!(fps > 0.0 && skip > 0 && scale > 0);
+        let format = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gif") => Format::Gif,
+            Some("png") => Format::Png,
+            _ => {
+                return Err(Error::new(format!(
+                    "cannot render to {}, the file must end in .gif or .png",
+                    path.display()
+                )))
+            }
+        };
+
+        let mut recorder = Self {
+            path: path.to_owned(),
+            format,
+            palette,
+            scale,
+            frame_delay: (100.0 / fps).round().clamp(1.0, u16::MAX as f64) as u16,
+            skip,
+            num_frames: 0,
+            spool: None,
+            max_size: (1, 1),
+            colors: vec![palette.background],
+            color_indices: HashMap::new(),
+            num_written: 0,
+            error: None,
+        };
+        if format == Format::Gif {
+            let spool_path = recorder.spool_path();
+            let file = File::create(&spool_path).map_err(|err| {
+                Error::new(format!("could not create {}: {err}", spool_path.display()))
+            })?;
+            recorder.spool = Some(BufWriter::new(file));
+        }
+        Ok(recorder)
+    }
+
+    // E.g. `out.gif.frames` for `out.gif`
+    fn spool_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".frames");
+        path.into()
+    }
+
+    fn spool_error(&self, err: impl fmt::Display) -> Error {
+        Error::new(format!(
+            "could not write {}: {err}",
+            self.spool_path().display()
+        ))
+    }
+
+    // The path of the `index`th PNG file, e.g. `out-0012.png` for `out.png`
+    fn png_path(&self, index: usize) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy())
+            .unwrap_or_default();
+        self.path.with_file_name(format!("{stem}-{index:04}.png"))
+    }
+
+    fn color_index(&mut self, c: char) -> Result<u8> {
+        if let Some(index) = self.color_indices.get(&c) {
+            return Ok(*index);
+        }
+
+        let color = self.palette.color(c);
+        let index = match self.colors.iter().position(|other| *other == color) {
+            Some(index) => index,
+            None => {
+                self.colors.push(color);
+                self.colors.len() - 1
+            }
+        };
+        let index =
+            u8::try_from(index).map_err(|_| Error::new("a GIF can't have more than 256 colors"))?;
+        self.color_indices.insert(c, index);
+        Ok(index)
+    }
+
+    fn scaled_size(&self, width: usize, height: usize) -> Result<(u16, u16)> {
+        let too_large = || Error::new("the frames are too large to render");
+        Ok((
+            u16::try_from(width * self.scale).map_err(|_| too_large())?,
+            u16::try_from(height * self.scale).map_err(|_| too_large())?,
+        ))
+    }
+
+    fn write_png(&self, frame: &Grid<char>, index: usize) -> Result<()> {
+        let (width, height) = self.scaled_size(frame.width(), frame.height())?;
+        let mut data = Vec::with_capacity(width as usize * height as usize * 3);
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                let p = Point::new((x / self.scale) as i32, (y / self.scale) as i32);
+                data.extend(self.palette.color(frame[p]));
+            }
+        }
+
+        let path = self.png_path(index);
+        let error = |err: png::EncodingError| {
+            Error::new(format!("could not write {}: {err}", path.display()))
+        };
+        let file = File::create(&path)
+            .map_err(|err| Error::new(format!("could not create {}: {err}", path.display())))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&data))
+            .map_err(error)
+    }
+
+    fn spool_gif_frame(&mut self, frame: &Grid<char>) -> Result<()> {
+        self.scaled_size(frame.width(), frame.height())?;
+        let indices = frame
+            .iter()
+            .map(|(_, c)| self.color_index(*c))
+            .collect::<Result<Vec<u8>>>()?;
+        let compressed = weezl::encode::Encoder::new(BitOrder::Lsb, 8)
+            .encode(&indices)
+            .map_err(|err| self.spool_error(err))?;
+
+        let spool = self.spool.as_mut().unwrap();
+        let result = [frame.width(), frame.height(), compressed.len()]
+            .iter()
+            .try_for_each(|n| spool.write_all(&(*n as u64).to_le_bytes()))
+            .and_then(|_| spool.write_all(&compressed));
+        result.map_err(|err| self.spool_error(err))?;
+
+        self.max_size = (
+            self.max_size.0.max(frame.width()),
+            self.max_size.1.max(frame.height()),
+        );
+        Ok(())
+    }
+
+    // Reads the next frame from the spool as its width, height and indices
+    fn read_spooled_frame(reader: &mut impl Read) -> io::Result<Option<(usize, usize, Vec<u8>)>> {
+        let mut header = [0; 24];
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        let [width, height, len] = array::from_fn(|i| {
+            u64::from_le_bytes(header[i * 8..][..8].try_into().unwrap()) as usize
+        });
+        let mut compressed = vec![0; len];
+        reader.read_exact(&mut compressed)?;
+        let indices = weezl::decode::Decoder::new(BitOrder::Lsb, 8)
+            .decode(&compressed)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(Some((width, height, indices)))
+    }
+
+    fn write_gif(&mut self) -> Result<()> {
+        let spool_path = self.spool_path();
+        let read_error =
+            |err: io::Error| Error::new(format!("could not read {}: {err}", spool_path.display()));
+        let mut spool = self.spool.take().unwrap();
+        spool.flush().map_err(|err| self.spool_error(err))?;
+        drop(spool);
+        let mut reader = BufReader::new(File::open(&spool_path).map_err(read_error)?);
+
+        let (width, height) = self.scaled_size(self.max_size.0, self.max_size.1)?;
+
+        let error = |err: gif::EncodingError| {
+            Error::new(format!("could not write {}: {err}", self.path.display()))
+        };
+        let file = File::create(&self.path).map_err(|err| {
+            Error::new(format!("could not create {}: {err}", self.path.display()))
+        })?;
+        let mut encoder =
+            gif::Encoder::new(BufWriter::new(file), width, height, &self.colors.concat())
+                .map_err(error)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(error)?;
+
+        // Every frame covers the whole image, with the background around it
+        while let Some((frame_width, _, indices)) =
+            Self::read_spooled_frame(&mut reader).map_err(read_error)?
+        {
+            let mut buffer = vec![0; width as usize * height as usize];
+            for (i, index) in indices.iter().enumerate() {
+                let (x, y) = (i % frame_width, i / frame_width);
+                for dy in 0..self.scale {
+                    let start = (y * self.scale + dy) * width as usize + x * self.scale;
+                    buffer[start..start + self.scale].fill(*index);
+                }
+            }
+            encoder
+                .write_frame(&gif::Frame {
+                    width,
+                    height,
+                    delay: self.frame_delay,
+                    buffer: Cow::Owned(buffer),
+                    ..Default::default()
+                })
+                .map_err(error)?;
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, frame: &Grid<char>) -> Result<()> {
+        match self.format {
+            Format::Gif => self.spool_gif_frame(frame)?,
+            Format::Png => self.write_png(frame, self.num_written)?,
+        }
+        self.num_written += 1;
+        Ok(())
+    }
+
+    // Writes the GIF, if that is the format, and returns the number of frames
+    // that were rendered
+    pub fn finish(mut self) -> Result<usize> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        if self.format == Format::Gif {
+            if self.num_written == 0 {
+                return Err(Error::new("there were no frames to render"));
+            }
+            self.write_gif()?;
+        }
+        Ok(self.num_written)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if self.format == Format::Gif {
+            self.spool = None;
+            let _ = fs::remove_file(self.spool_path());
+        }
+    }
+}
+
+impl Observer for Recorder {
+    fn is_active(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn wants_frame(&mut self) -> bool {
+        let wanted = self.error.is_none() && self.num_frames.is_multiple_of(self.skip);
+        self.num_frames += 1;
+        wanted
+    }
+
+    fn frame(&mut self, frame: &Grid<char>) {
+        if let Err(err) = self.record(frame) {
+            self.error = Some(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn frames() -> Vec<Grid<char>> {
+        vec![
+            Grid::new(2, 1, '#'),
+            Grid::new(1, 3, '.'),
+            Grid::new(3, 2, 'o'),
+        ]
+    }
+
+    #[test]
+    fn test_gif() {
+        let dir = temp_dir("gif");
+        let path = dir.join("out.gif");
+
+        let mut recorder = Recorder::new(&path, Palette::DEFAULT, 10.0, 1, 2).unwrap();
+        for frame in frames() {
+            let observer: &mut dyn Observer = &mut recorder;
+            observer.show(|| frame);
+        }
+        assert_eq!(recorder.finish(), Ok(3));
+        assert!(!dir.join("out.gif.frames").exists());
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(File::open(&path).unwrap()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (6, 6));
+
+        let first = decoder.read_next_frame().unwrap().unwrap();
+        assert_eq!(first.delay, 10);
+        // The top left pixel is a '#' and the bottom right one is background
+        assert_eq!(first.buffer[..3], Palette::DEFAULT.color('#'));
+        assert_eq!(
+            first.buffer[first.buffer.len() - 4..][..3],
+            Palette::DEFAULT.background
+        );
+        let mut num_frames = 1;
+        while decoder.read_next_frame().unwrap().is_some() {
+            num_frames += 1;
+        }
+        assert_eq!(num_frames, 3);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_png() {
+        let dir = temp_dir("png");
+        let path = dir.join("out.png");
+
+        // Only the first and the third frame are rendered
+        let mut recorder = Recorder::new(&path, Palette::DEFAULT, 10.0, 2, 1).unwrap();
+        for frame in frames() {
+            let observer: &mut dyn Observer = &mut recorder;
+            observer.show(|| frame);
+        }
+        assert_eq!(recorder.finish(), Ok(2));
+
+        let decoder = png::Decoder::new(File::open(dir.join("out-0001.png")).unwrap());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (3, 2));
+        assert!(!dir.join("out-0002.png").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_path() {
+        assert!(Recorder::new(Path::new("out.txt"), Palette::DEFAULT, 10.0, 1, 1).is_err());
+        assert!(Recorder::new(Path::new("out"), Palette::DEFAULT, 10.0, 1, 1).is_err());
+    }
+}
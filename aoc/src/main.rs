@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use aoc_core::solution::Registry;
+
+fn main() -> ExitCode {
+    let mut registry = Registry::new();
+    aoc2022::register(&mut registry);
+    aoc_core::cli::main(&registry)
+}
@@ -1,52 +1,77 @@
 use std::mem::swap;
 
-pub fn part1(input: &[String]) -> u32 {
-    input
-        .join("\n")
-        .split("\n\n")
-        .map(|l| {
-            l.split('\n')
-                .map(|c| c.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .max()
-        .unwrap()
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
+// Returns the total calories carried by each elf
+pub fn parse(input: &str) -> Result<Vec<u32>> {
+    let mut calories_per_elf = vec![0];
+    for (i, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            calories_per_elf.push(0);
+            continue;
+        }
+        let calories = line
+            .parse::<u32>()
+            .map_err(|_| Error::at_line(i + 1, format!("invalid calorie count \"{line}\"")))?;
+        *calories_per_elf.last_mut().unwrap() += calories;
+    }
+    Ok(calories_per_elf)
+}
+
+pub fn part1(calories_per_elf: &[u32]) -> Result<u32> {
+    Ok(calories_per_elf.iter().copied().max().unwrap_or_default())
 }
 
-pub fn part2(input: &[String]) -> u32 {
+pub fn part2(calories_per_elf: &[u32]) -> Result<u32> {
     let mut max: Vec<u32> = vec![0; 3];
-    input
-        .join("\n")
-        .split("\n\n")
-        .map(|l| {
-            l.split('\n')
-                .map(|c| c.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .for_each(|mut c| {
-            for m in max.iter_mut() {
-                if c > *m {
-                    swap(&mut c, m);
-                }
+    calories_per_elf.iter().for_each(|&(mut c)| {
+        for m in max.iter_mut() {
+            if c > *m {
+                swap(&mut c, m);
             }
-        });
-    max.iter().sum()
+        }
+    });
+    Ok(max.iter().sum())
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 1;
+
+    type Input<'a> = Vec<u32>;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<u32>> {
+        parse(input)
+    }
+
+    fn part1(&self, calories_per_elf: &Vec<u32>) -> Result<u32> {
+        part1(calories_per_elf)
+    }
+
+    fn part2(&self, calories_per_elf: &Vec<u32>) -> Result<u32> {
+        part2(calories_per_elf)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day1.txt")), 24000);
-        assert_eq!(part1(&read_input_from_file("input/day1.txt")), 69281);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day1, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day1.txt")), 45000);
-        assert_eq!(part2(&read_input_from_file("input/day1.txt")), 201524);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day1, Part::Two)
     }
 }
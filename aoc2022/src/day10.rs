@@ -1,27 +1,38 @@
-fn simulate_register(input: &[String]) -> Vec<i32> {
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
+// Returns the value of the register during each cycle
+pub fn parse(input: &str) -> Result<Vec<i32>> {
     let mut result: Vec<i32> = Vec::new();
     let mut register: i32 = 1;
-    for line in input {
+    for (i, line) in input.lines().enumerate() {
         result.push(register);
-        if line.starts_with("addx") {
-            result.push(register);
-            register += line[5..].parse::<i32>().unwrap();
+        if line == "noop" {
+            continue;
         }
+        let value = line
+            .strip_prefix("addx ")
+            .and_then(|value| value.parse::<i32>().ok())
+            .ok_or_else(|| Error::at_line(i + 1, format!("invalid instruction \"{line}\"")))?;
+        result.push(register);
+        register += value;
     }
-    result
+    Ok(result)
 }
 
-pub fn part1(input: &[String]) -> i32 {
-    simulate_register(input)
+pub fn part1(register: &[i32]) -> Result<i32> {
+    Ok(register
         .iter()
         .enumerate()
         .filter(|(i, _)| (i + 20 + 1) % 40 == 0)
         .map(|(i, value)| ((i as i32) + 1) * value)
-        .sum()
+        .sum())
 }
 
-pub fn part2(input: &[String]) -> String {
-    simulate_register(input)
+pub fn part2(register: &[i32]) -> Result<String> {
+    Ok(register
         .iter()
         .enumerate()
         .map(|(i, value)| {
@@ -40,25 +51,44 @@ pub fn part2(input: &[String]) -> String {
 
             pixel
         })
-        .collect::<String>()
+        .collect::<String>())
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 10;
+
+    type Input<'a> = Vec<i32>;
+    type Output1 = i32;
+    type Output2 = String;
+
+    fn parse(&self, input: &str) -> Result<Vec<i32>> {
+        parse(input)
+    }
+
+    fn part1(&self, register: &Vec<i32>) -> Result<i32> {
+        part1(register)
+    }
+
+    fn part2(&self, register: &Vec<i32>) -> Result<String> {
+        part2(register)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day10.txt")), 13140);
-        assert_eq!(part1(&read_input_from_file("input/day10.txt")), 14360);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day10, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day10.txt")), "\n##..##..##..##..##..##..##..##..##..##..\n###...###...###...###...###...###...###.\n####....####....####....####....####....\n#####.....#####.....#####.....#####.....\n######......######......######......####\n#######.......#######.......#######.....");
-
-        // Correct part result is "BGKAEREZ"
-        assert_eq!(part2(&read_input_from_file("input/day10.txt")), "\n###...##..#..#..##..####.###..####.####.\n#..#.#..#.#.#..#..#.#....#..#.#.......#.\n###..#....##...#..#.###..#..#.###....#..\n#..#.#.##.#.#..####.#....###..#.....#...\n#..#.#..#.#.#..#..#.#....#.#..#....#....\n###...###.#..#.#..#.####.#..#.####.####.");
+    fn test_part2() -> Result<()> {
+        check_answers(&Day10, Part::Two)
     }
 }
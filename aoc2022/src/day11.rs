@@ -1,10 +1,23 @@
 use std::mem::swap;
 
-#[derive(Debug)]
-struct Monkey {
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::parse::Parser,
+};
+
+#[derive(Debug, Clone, Copy)]
+enum Operator {
+    Add,
+    Multiply,
+}
+
+#[derive(Debug, Clone)]
+pub struct Monkey {
     items: Vec<i128>,
-    operator: String,
-    operator_argument: String,
+    operator: Operator,
+    // None if the operation uses the old value as argument
+    operator_argument: Option<i128>,
     test_divide_by: i128,
     send_if_true: usize,
     send_if_false: usize,
@@ -12,42 +25,64 @@ struct Monkey {
 }
 
 impl Monkey {
-    fn new(
-        item_line: &str,
-        operation_line: &str,
-        divide_line: &str,
-        true_line: &str,
-        false_line: &str,
-    ) -> Self {
-        let mut monkey = Monkey {
-            items: Vec::new(),
-            operator: operation_line[23..24].to_string(),
-            operator_argument: operation_line[25..].to_string(),
-            test_divide_by: divide_line[21..].parse().unwrap(),
-            send_if_true: true_line[29..].parse().unwrap(),
-            send_if_false: false_line[30..].parse().unwrap(),
-            number_of_inspected_items: 0,
-        };
+    fn parse(parser: &mut Parser, index: usize) -> Result<Self> {
+        parser.literal("Monkey")?;
+        let line = parser.line();
+        if parser.number::<usize>()? != index {
+            return Err(Error::at_line(line, format!("expected monkey {index}")));
+        }
+        parser.literal(":")?;
+        parser.end_of_line()?;
+
+        parser.literal("Starting items:")?;
+        let items = parser.separated(",", Parser::number)?;
+        parser.end_of_line()?;
 
-        for elem in item_line[18..].split(", ") {
-            monkey.items.push(elem.parse().unwrap());
+        parser.literal("Operation: new = old")?;
+        let operator = parser.one_of(&[("+", Operator::Add), ("*", Operator::Multiply)])?;
+        let operator_argument = if parser.try_literal("old") {
+            None
+        } else {
+            Some(parser.number()?)
+        };
+        parser.end_of_line()?;
+
+        parser.literal("Test: divisible by")?;
+        let test_divide_by = parser.number()?;
+        if test_divide_by <= 0 {
+            return Err(Error::at_line(
+                parser.line(),
+                "test divisor must be positive",
+            ));
         }
-        monkey
+        parser.end_of_line()?;
+
+        parser.literal("If true: throw to monkey")?;
+        let send_if_true = parser.number()?;
+        parser.end_of_line()?;
+        parser.literal("If false: throw to monkey")?;
+        let send_if_false = parser.number()?;
+        parser.end_of_line()?;
+
+        Ok(Monkey {
+            items,
+            operator,
+            operator_argument,
+            test_divide_by,
+            send_if_true,
+            send_if_false,
+            number_of_inspected_items: 0,
+        })
     }
 
     fn inspect(&mut self, divide_by: i128, mod_value_by: i128) -> Option<(i128, usize)> {
         let mut item = self.items.pop()?;
 
-        let int_to_apply: i128 = if self.operator_argument == "old" {
-            item
-        } else {
-            self.operator_argument.parse::<i128>().unwrap()
-        };
+        let int_to_apply: i128 = self.operator_argument.unwrap_or(item);
 
-        if self.operator == "+" {
-            item += int_to_apply;
-        } else {
-            item *= int_to_apply;
+        match self.operator {
+            Operator::Add => item += int_to_apply,
+            Operator::Multiply => item *= int_to_apply,
         }
 
         item /= divide_by;
@@ -63,23 +98,31 @@ impl Monkey {
     }
 }
 
-fn parse_monkeys(input: &[String]) -> Vec<Monkey> {
+pub fn parse(input: &str) -> Result<Vec<Monkey>> {
+    let mut parser = Parser::new(input);
     let mut res: Vec<Monkey> = Vec::new();
+    // The first line of every monkey, for the errors below
+    let mut lines = Vec::new();
+
+    parser.blank_lines();
+    while !parser.is_empty() {
+        lines.push(parser.line());
+        res.push(Monkey::parse(&mut parser, res.len())?);
+        parser.blank_lines();
+    }
 
-    let mut monkey_iter = input.iter();
-
-    while let Some(_) = monkey_iter.next() {
-        res.push(Monkey::new(
-            monkey_iter.next().unwrap(),
-            monkey_iter.next().unwrap(),
-            monkey_iter.next().unwrap(),
-            monkey_iter.next().unwrap(),
-            monkey_iter.next().unwrap(),
+    let num_monkeys = res.len();
+    if let Some(i) = res
+        .iter()
+        .position(|m| m.send_if_true >= num_monkeys || m.send_if_false >= num_monkeys)
+    {
+        return Err(Error::at_line(
+            lines[i],
+            "monkey throws to a monkey that does not exist",
         ));
-        monkey_iter.next();
     }
 
-    res
+    Ok(res)
 }
 
 fn simulate_monkeys(monkeys: &mut [Monkey], num_iterations: usize, divide_worry_by: i128) {
@@ -94,8 +137,8 @@ fn simulate_monkeys(monkeys: &mut [Monkey], num_iterations: usize, divide_worry_
     }
 }
 
-pub fn part1(input: &[String]) -> usize {
-    let mut monkeys = parse_monkeys(input);
+pub fn part1(monkeys: &[Monkey]) -> Result<usize> {
+    let mut monkeys = monkeys.to_vec();
     simulate_monkeys(&mut monkeys, 20, 3);
 
     let mut max1 = 0usize;
@@ -108,11 +151,11 @@ pub fn part1(input: &[String]) -> usize {
             swap(&mut monkey.number_of_inspected_items, &mut max2);
         }
     }
-    max1 * max2
+    Ok(max1 * max2)
 }
 
-pub fn part2(input: &[String]) -> usize {
-    let mut monkeys = parse_monkeys(input);
+pub fn part2(monkeys: &[Monkey]) -> Result<usize> {
+    let mut monkeys = monkeys.to_vec();
     simulate_monkeys(&mut monkeys, 10000, 1);
 
     let mut max1 = 0usize;
@@ -125,23 +168,44 @@ pub fn part2(input: &[String]) -> usize {
             swap(&mut monkey.number_of_inspected_items, &mut max2);
         }
     }
-    max1 * max2
+    Ok(max1 * max2)
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 11;
+
+    type Input<'a> = Vec<Monkey>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<Monkey>> {
+        parse(input)
+    }
+
+    fn part1(&self, monkeys: &Vec<Monkey>) -> Result<usize> {
+        part1(monkeys)
+    }
+
+    fn part2(&self, monkeys: &Vec<Monkey>) -> Result<usize> {
+        part2(monkeys)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day11.txt")), 10605);
-        assert_eq!(part1(&read_input_from_file("input/day11.txt")), 72884);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day11, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day11.txt")), 2713310158);
-        assert_eq!(part2(&read_input_from_file("input/day11.txt")), 15310845153);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day11, Part::Two)
     }
 }
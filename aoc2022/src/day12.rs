@@ -1,109 +1,107 @@
-use crate::util::Point;
-use std::collections::{HashSet, VecDeque};
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::{search::Search, Grid, Point},
+};
 
-struct HeightMap {
-    heights: Vec<Vec<u32>>,
+pub struct HeightMap {
+    heights: Grid<u32>,
     start: Point<i32>,
     end: Point<i32>,
-    width: usize,
-    height: usize,
 }
 
 impl HeightMap {
-    fn new(input: &[String]) -> HeightMap {
-        let mut map = HeightMap {
-            heights: vec![Vec::new(); input.len()],
-            start: Point { x: 0, y: 0 },
-            end: Point { x: 0, y: 0 },
-            width: input[0].len(),
-            height: input.len(),
-        };
-
-        for (y, line) in input.iter().enumerate() {
-            for (x, mut c) in line.chars().enumerate() {
-                if c == 'S' {
-                    map.start = Point {
-                        x: x.try_into().unwrap(),
-                        y: y.try_into().unwrap(),
-                    };
-                    c = 'a';
-                } else if c == 'E' {
-                    map.end = Point {
-                        x: x.try_into().unwrap(),
-                        y: y.try_into().unwrap(),
-                    };
-                    c = 'z';
-                }
-                map.heights[y].push((c as u32) - 97);
-            }
-        }
-        map
-    }
+    fn new(input: &str) -> Result<HeightMap> {
+        let grid = Grid::parse(input, |c| {
+            (c.is_ascii_lowercase() || c == 'S' || c == 'E').then_some(c)
+        })?;
 
-    fn height_at(&self, p: &Point<i32>) -> u32 {
-        self.heights[p.y as usize][p.x as usize]
+        Ok(HeightMap {
+            start: grid
+                .position(|c| *c == 'S')
+                .ok_or_else(|| Error::new("there is no start position"))?,
+            end: grid
+                .position(|c| *c == 'E')
+                .ok_or_else(|| Error::new("there is no end position"))?,
+            heights: grid.map(|c| match c {
+                'S' => 0,
+                'E' => 25,
+                _ => (*c as u32) - 97,
+            }),
+        })
     }
 
-    fn find_path_length<T>(&self, start: &Point<i32>, stop_condition: &T) -> Option<usize>
-    where
-        T: Fn(&Point<i32>) -> bool,
-    {
-        let mut visited: HashSet<Point<i32>> = HashSet::new();
-        let mut queue: VecDeque<(Point<i32>, usize)> = VecDeque::new();
-
-        visited.insert(*start);
-        queue.push_back((*start, 0));
-
-        while let Some((node, num_steps)) = queue.pop_front() {
-            if stop_condition(&node) {
-                return Some(num_steps);
-            }
-
-            visited.insert(node);
-            for neighbor in node.get_manhattan_neighbors() {
-                if neighbor.is_within(
-                    0,
-                    0,
-                    self.width.try_into().unwrap(),
-                    self.height.try_into().unwrap(),
-                ) && !visited.contains(&neighbor)
-                    && self.height_at(&neighbor) + 1 >= self.height_at(&node)
-                {
-                    visited.insert(neighbor);
-                    queue.push_back((neighbor, num_steps + 1));
-                }
-            }
-        }
-        None
+    fn find_path_length(
+        &self,
+        start: Point<i32>,
+        is_goal: impl FnMut(&Point<i32>) -> bool,
+    ) -> Option<usize> {
+        // Searches backwards from the end, so only steps that go up by at
+        // most one when reversed are allowed
+        Search::new()
+            .bfs(
+                start,
+                |p| {
+                    self.heights
+                        .manhattan_neighbors(*p)
+                        .filter(|n| self.heights[*n] + 1 >= self.heights[*p])
+                        .collect::<Vec<_>>()
+                },
+                is_goal,
+            )
+            .map(|(_, steps)| steps)
     }
 }
 
-pub fn part1(input: &[String]) -> usize {
-    let map = HeightMap::new(input);
-    map.find_path_length(&map.end, &|node| *node == map.start)
-        .expect("No solution found!")
+pub fn parse(input: &str) -> Result<HeightMap> {
+    HeightMap::new(input)
+}
+
+pub fn part1(map: &HeightMap) -> Result<usize> {
+    map.find_path_length(map.end, |p| *p == map.start)
+        .ok_or_else(|| Error::new("no path found"))
 }
 
-pub fn part2(input: &[String]) -> usize {
-    let map = HeightMap::new(input);
-    map.find_path_length(&map.end, &|node| map.height_at(node) == 0)
-        .expect("No solution found!")
+pub fn part2(map: &HeightMap) -> Result<usize> {
+    map.find_path_length(map.end, |p| map.heights[*p] == 0)
+        .ok_or_else(|| Error::new("no path found"))
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 12;
+
+    type Input<'a> = HeightMap;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(&self, input: &str) -> Result<HeightMap> {
+        parse(input)
+    }
+
+    fn part1(&self, map: &HeightMap) -> Result<usize> {
+        part1(map)
+    }
+
+    fn part2(&self, map: &HeightMap) -> Result<usize> {
+        part2(map)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day12.txt")), 31);
-        assert_eq!(part1(&read_input_from_file("input/day12.txt")), 490);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day12, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day12.txt")), 29);
-        assert_eq!(part2(&read_input_from_file("input/day12.txt")), 488);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day12, Part::Two)
     }
 }
@@ -1,7 +1,12 @@
 use std::{cmp::Ordering, str::FromStr};
 
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
 #[derive(PartialEq, Eq, Debug)]
-enum Packet {
+pub enum Packet {
     Integer(i32),
     List(Vec<Packet>),
 }
@@ -37,12 +42,12 @@ impl Ord for Packet {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct ParsePacketError;
+pub struct ParsePacketError;
 
 impl FromStr for Packet {
     type Err = ParsePacketError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         let input = s
             .strip_prefix('[')
             .and_then(|s| s.strip_suffix(']'))
@@ -71,7 +76,9 @@ impl FromStr for Packet {
 }
 
 impl Packet {
-    fn parse_packet_from_iterator<'a, I>(input_iter: &mut I) -> Result<Self, ParsePacketError>
+    fn parse_packet_from_iterator<'a, I>(
+        input_iter: &mut I,
+    ) -> std::result::Result<Self, ParsePacketError>
     where
         I: Iterator<Item = &'a str>,
     {
@@ -91,54 +98,75 @@ impl Packet {
     }
 }
 
-pub fn part1(input: &[String]) -> usize {
-    let packets: Vec<Packet> = input
-        .iter()
-        .filter(|line| !line.is_empty())
-        .map(|line| line.parse().unwrap())
-        .collect();
+pub fn parse(input: &str) -> Result<Vec<Packet>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|_| Error::at_line(i + 1, format!("invalid packet \"{line}\"")))
+        })
+        .collect()
+}
 
-    packets
+pub fn part1(packets: &[Packet]) -> Result<usize> {
+    Ok(packets
         .chunks_exact(2)
         .enumerate()
         .filter(|(_, packets)| packets[0] < packets[1])
         .map(|(i, _)| i + 1)
-        .sum()
+        .sum())
 }
 
-pub fn part2(input: &[String]) -> usize {
+pub fn part2(packets: &[Packet]) -> Result<usize> {
     // I assume that the divider packets are unique and the input will not
     // contain any packets identical to those. If there were, the problem would
     // not have a definite solution since the order of those packets isn't
     // specified.
 
-    let packets: Vec<Packet> = input
-        .iter()
-        .filter(|line| !line.is_empty())
-        .map(|line| line.parse().unwrap())
-        .collect();
-
     let p1 = "[[2]]".parse::<Packet>().unwrap();
     let p2 = "[[6]]".parse::<Packet>().unwrap();
 
-    (packets.iter().filter(|packet| *packet < &p1).count() + 1)
-        * (packets.iter().filter(|packet| *packet < &p2).count() + 2)
+    Ok((packets.iter().filter(|packet| *packet < &p1).count() + 1)
+        * (packets.iter().filter(|packet| *packet < &p2).count() + 2))
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 13;
+
+    type Input<'a> = Vec<Packet>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<Packet>> {
+        parse(input)
+    }
+
+    fn part1(&self, packets: &Vec<Packet>) -> Result<usize> {
+        part1(packets)
+    }
+
+    fn part2(&self, packets: &Vec<Packet>) -> Result<usize> {
+        part2(packets)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day13.txt")), 13);
-        assert_eq!(part1(&read_input_from_file("input/day13.txt")), 5808);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day13, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day13.txt")), 140);
-        assert_eq!(part2(&read_input_from_file("input/day13.txt")), 22713);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day13, Part::Two)
     }
 }
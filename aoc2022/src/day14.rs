@@ -1,34 +1,36 @@
-use crate::util::{ParsePointError, Point};
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::{Grid, Point},
+    visualize::{NoObserver, Observer, Palette},
+};
 use std::cmp;
 
+const AIR: char = '.';
+const ROCK: char = '#';
+const SAND: char = 'o';
+
 struct RockMaze {
-    grid: Vec<Vec<bool>>,
-    array_offset: usize,
-    height: usize,
+    grid: Grid<char>,
+    // The x coordinate of the leftmost column of the grid, which is negative
+    // if the floor reaches further left than x = 0
+    array_offset: i32,
+    height: i32,
     amount_of_sand: usize,
     next_start_pos: Point<i32>,
 }
 
 impl RockMaze {
-    fn new(input: &[String], add_floor: bool) -> Result<Self, ParsePointError> {
-        let rock_rows: Vec<Vec<Point<i32>>> = input
-            .iter()
-            .map(|line| {
-                line.split(" -> ")
-                    .map(|s| s.parse::<Point<i32>>())
-                    .collect::<Result<Vec<Point<i32>>, ParsePointError>>()
-            })
-            .collect::<Result<Vec<Vec<Point<i32>>>, ParsePointError>>()?;
-
-        let mut x_min: usize = 500;
-        let mut x_max: usize = 500;
-        let mut y_max: usize = 0;
+    fn new(rock_rows: &[Vec<Point<i32>>], add_floor: bool) -> Self {
+        let mut x_min = 500;
+        let mut x_max = 500;
+        let mut y_max = 0;
 
         for rocks in rock_rows.iter() {
             for rock in rocks {
-                x_min = cmp::min(x_min, (rock.x) as usize);
-                x_max = cmp::max(x_max, (rock.x) as usize);
-                y_max = cmp::max(y_max, (rock.y) as usize);
+                x_min = cmp::min(x_min, rock.x());
+                x_max = cmp::max(x_max, rock.x());
+                y_max = cmp::max(y_max, rock.y());
             }
         }
 
@@ -44,76 +46,71 @@ impl RockMaze {
         x_max += 1;
 
         let mut rock_maze = Self {
-            grid: vec![vec![false; x_max - x_min + 1]; y_max + 1],
+            grid: Grid::new((x_max - x_min + 1) as usize, (y_max + 1) as usize, AIR),
             array_offset: x_min,
             height: y_max,
             amount_of_sand: 0,
-            next_start_pos: Point { x: 500, y: 0 },
+            next_start_pos: Point::new(500, 0),
         };
 
         for rocks in rock_rows {
             rocks.windows(2).for_each(|points| {
-                let step = Point {
-                    x: (points[1].x - points[0].x).signum(),
-                    y: (points[1].y - points[0].y).signum(),
-                };
-
-                // Asserts that exactly one of x and y are 0
-                assert!((step.x == 0) ^ (step.y == 0));
+                let step = Point::new(
+                    (points[1].x() - points[0].x()).signum(),
+                    (points[1].y() - points[0].y()).signum(),
+                );
 
                 for i in 0..=cmp::max(
-                    (points[1].x - points[0].x).abs(),
-                    (points[1].y - points[0].y).abs(),
+                    (points[1].x() - points[0].x()).abs(),
+                    (points[1].y() - points[0].y()).abs(),
                 ) {
-                    rock_maze.set_content_with_point(&(points[0] + step * i), true);
+                    rock_maze.set_content_with_point(&(points[0] + step * i), ROCK);
                 }
             })
         }
 
         if add_floor {
             for x in (500 - rock_maze.height)..=(500 + rock_maze.height) {
-                rock_maze.set_content(x, rock_maze.height, true);
+                rock_maze.set_content_with_point(&Point::new(x, rock_maze.height), ROCK);
             }
         }
-        Ok(rock_maze)
+        rock_maze
     }
 
-    fn get_content_with_point(&self, p: &Point<i32>) -> bool {
-        self.grid[p.y as usize][(p.x as usize) - self.array_offset]
+    fn to_grid_point(&self, p: &Point<i32>) -> Point<i32> {
+        Point::new(p.x() - self.array_offset, p.y())
     }
 
-    fn set_content(&mut self, x: usize, y: usize, content: bool) {
-        self.grid[y][x - self.array_offset] = content;
+    fn is_blocked(&self, p: &Point<i32>) -> bool {
+        self.grid[self.to_grid_point(p)] != AIR
     }
 
-    fn set_content_with_point(&mut self, p: &Point<i32>, content: bool) {
-        self.grid[p.y as usize][(p.x as usize) - self.array_offset] = content;
+    fn set_content_with_point(&mut self, p: &Point<i32>, content: char) {
+        let p = self.to_grid_point(p);
+        self.grid[p] = content;
     }
 
-    const POSSIBLE_NEIGHBORS: [Point<i32>; 3] = [
-        Point { x: 0, y: 1 },
-        Point { x: -1, y: 1 },
-        Point { x: 1, y: 1 },
-    ];
+    const POSSIBLE_NEIGHBORS: [Point<i32>; 3] =
+        [Point::new(0, 1), Point::new(-1, 1), Point::new(1, 1)];
 
     fn simulate_sand(&mut self) -> bool {
-        if self.get_content_with_point(&self.next_start_pos) {
+        if self.is_blocked(&self.next_start_pos) {
             // Resets the starting position
-            self.next_start_pos = Point { x: 500, y: 0 };
-            if self.get_content_with_point(&self.next_start_pos) {
+            self.next_start_pos = Point::new(500, 0);
+            if self.is_blocked(&self.next_start_pos) {
                 return false;
             }
         }
 
         let mut current_point = self.next_start_pos;
 
-        while (current_point.y as usize) < self.height {
+        while current_point.y() < self.height {
             let mut found_empty_space = false;
 
             for step in Self::POSSIBLE_NEIGHBORS {
                 let next_point = current_point + step;
 
-                if !self.get_content_with_point(&next_point) {
+                if !self.is_blocked(&next_point) {
                     self.next_start_pos = current_point;
                     current_point = next_point;
                     found_empty_space = true;
@@ -122,7 +119,7 @@ impl RockMaze {
             }
 
             if !found_empty_space {
-                self.set_content_with_point(&current_point, true);
+                self.set_content_with_point(&current_point, SAND);
                 self.amount_of_sand += 1;
                 return true;
             }
@@ -131,36 +128,133 @@ impl RockMaze {
     }
 }
 
-pub fn part1(input: &[String]) -> usize {
-    let mut maze = RockMaze::new(input, false).unwrap();
+// Returns the points along each rock path
+pub fn parse(input: &str) -> Result<Vec<Vec<Point<i32>>>> {
+    let rock_rows: Vec<Vec<Point<i32>>> = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.split(" -> ")
+                .map(|s| match s.parse::<Point<i32>>() {
+                    Ok(p) if p.x() >= 0 && p.y() >= 0 => Ok(p),
+                    _ => Err(Error::at_line(i + 1, format!("invalid point \"{s}\""))),
+                })
+                .collect::<Result<Vec<Point<i32>>>>()
+        })
+        .collect::<Result<Vec<Vec<Point<i32>>>>>()?;
 
-    while maze.simulate_sand() {}
+    if let Some(i) = rock_rows.iter().position(|rocks| {
+        rocks
+            .windows(2)
+            .any(|points| (points[0].x() == points[1].x()) == (points[0].y() == points[1].y()))
+    }) {
+        return Err(Error::at_line(
+            i + 1,
+            "rock paths must be horizontal or vertical",
+        ));
+    }
+    Ok(rock_rows)
+}
 
-    maze.amount_of_sand
+fn pour_sand(
+    rock_rows: &[Vec<Point<i32>>],
+    add_floor: bool,
+    observer: &mut dyn Observer,
+) -> Result<usize> {
+    let mut maze = RockMaze::new(rock_rows, add_floor);
+
+    while maze.simulate_sand() {
+        observer.show(|| maze.grid.clone());
+    }
+
+    Ok(maze.amount_of_sand)
+}
+
+pub fn part1(rock_rows: &[Vec<Point<i32>>], observer: &mut dyn Observer) -> Result<usize> {
+    pour_sand(rock_rows, false, observer)
 }
 
-pub fn part2(input: &[String]) -> usize {
-    let mut maze = RockMaze::new(input, true).unwrap();
+pub fn part2(rock_rows: &[Vec<Point<i32>>], observer: &mut dyn Observer) -> Result<usize> {
+    pour_sand(rock_rows, true, observer)
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 14;
+
+    type Input<'a> = Vec<Vec<Point<i32>>>;
+    type Output1 = usize;
+    type Output2 = usize;
 
-    while maze.simulate_sand() {}
+    const PALETTE: Palette = Palette {
+        background: [10, 10, 30],
+        colors: &[
+            ('.', [10, 10, 30]),
+            ('#', [120, 110, 100]),
+            ('o', [240, 200, 80]),
+        ],
+    };
 
-    maze.amount_of_sand
+    fn parse(&self, input: &str) -> Result<Vec<Vec<Point<i32>>>> {
+        parse(input)
+    }
+
+    fn part1(&self, rock_rows: &Vec<Vec<Point<i32>>>) -> Result<usize> {
+        part1(rock_rows, &mut NoObserver)
+    }
+
+    fn part2(&self, rock_rows: &Vec<Vec<Point<i32>>>) -> Result<usize> {
+        part2(rock_rows, &mut NoObserver)
+    }
+
+    fn visualize_part1(
+        &self,
+        rock_rows: &Vec<Vec<Point<i32>>>,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<usize>> {
+        Some(part1(rock_rows, observer))
+    }
+
+    fn visualize_part2(
+        &self,
+        rock_rows: &Vec<Vec<Point<i32>>>,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<usize>> {
+        Some(part2(rock_rows, observer))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
+
+    #[test]
+    fn test_floor_left_of_zero() -> Result<()> {
+        // The floor at y = 602 reaches from x = -102 to 1102
+        let maze = RockMaze::new(&parse("499,600 -> 501,600")?, true);
+        assert_eq!(maze.array_offset, -103);
+        assert!(maze.is_blocked(&Point::new(-102, 602)));
+        assert!(!maze.is_blocked(&Point::new(-102, 601)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        assert_eq!(parse("0,4 -> 0,6")?, [[Point::new(0, 4), Point::new(0, 6)]]);
+        assert!(parse("-1,4 -> 0,4").is_err());
+        Ok(())
+    }
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day14.txt")), 24);
-        assert_eq!(part1(&read_input_from_file("input/day14.txt")), 897);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day14, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day14.txt")), 93);
-        assert_eq!(part2(&read_input_from_file("input/day14.txt")), 26683);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day14, Part::Two)
     }
 }
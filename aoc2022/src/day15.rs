@@ -1,8 +1,12 @@
-use std::{cmp, str::FromStr};
+use std::ops::RangeInclusive;
 
 use itertools::Itertools;
 
-use crate::util::Point;
+use aoc_core::{
+    error::{Error, Result},
+    solution::{Params, Solution},
+    util::{parse::Parser, IntervalSet, Point},
+};
 
 #[derive(Debug, Clone)]
 struct Line {
@@ -12,8 +16,8 @@ struct Line {
 
 impl Line {
     fn from_points(p1: &Point<i32>, p2: &Point<i32>) -> Self {
-        let k = (p2.y - p1.y) / (p2.x - p1.x);
-        let m = p1.y - k * p1.x;
+        let k = (p2.y() - p1.y()) / (p2.x() - p1.x());
+        let m = p1.y() - k * p1.x();
         Self { k, m }
     }
 
@@ -25,47 +29,38 @@ impl Line {
         let x = (other.m - self.m) / (self.k - other.k);
         let y = self.k * x + self.m;
 
-        Some(Point { x, y })
+        Some(Point::new(x, y))
     }
 }
 
 #[derive(Debug)]
-struct Sensor {
+pub struct Sensor {
     location: Point<i32>,
     beacon: Point<i32>,
     range: usize,
 }
 
 impl Sensor {
-    fn new(x: i32, y: i32, beacon_x: i32, beacon_y: i32) -> Self {
-        let mut s = Self {
-            location: Point { x, y },
-            beacon: Point {
-                x: beacon_x,
-                y: beacon_y,
-            },
-            range: 0,
-        };
-        s.range = s.location.manhattan_distance_to(&s.beacon) as usize;
-        s
-    }
-
-    fn covers(&self, x: i32, y: i32) -> bool {
-        (self.location.manhattan_distance_to(&Point { x, y }) as usize) <= self.range
+    fn new(location: Point<i32>, beacon: Point<i32>) -> Self {
+        Self {
+            location,
+            beacon,
+            range: location.manhattan_distance_to(&beacon) as usize,
+        }
     }
 
     // Returns the range of x values that are covered by this sensor at the
-    // given y height (inclusive)
-    fn range_at_y(&self, y: i32) -> Option<(i32, i32)> {
+    // given y height
+    fn range_at_y(&self, y: i32) -> Option<RangeInclusive<i32>> {
         let range: i32 = self.range.try_into().unwrap();
-        if (self.location.y - y).abs() > range {
+        if (self.location.y() - y).abs() > range {
             return None;
         }
 
-        Some((
-            self.location.x - (range - (self.location.y - y).abs()),
-            self.location.x + (range - (self.location.y - y).abs()),
-        ))
+        Some(
+            self.location.x() - (range - (self.location.y() - y).abs())
+                ..=self.location.x() + (range - (self.location.y() - y).abs()),
+        )
     }
 
     fn get_lines(&self) -> [Line; 4] {
@@ -73,200 +68,149 @@ impl Sensor {
         [
             // Upper left
             Line::from_points(
-                &Point {
-                    x: self.location.x - (range + 1),
-                    y: self.location.y,
-                },
-                &Point {
-                    x: self.location.x,
-                    y: self.location.y - (range + 1),
-                },
+                &Point::new(self.location.x() - (range + 1), self.location.y()),
+                &Point::new(self.location.x(), self.location.y() - (range + 1)),
             ),
             // Upper right
             Line::from_points(
-                &Point {
-                    x: self.location.x,
-                    y: self.location.y - (range + 1),
-                },
-                &Point {
-                    x: self.location.x + (range + 1),
-                    y: self.location.y,
-                },
+                &Point::new(self.location.x(), self.location.y() - (range + 1)),
+                &Point::new(self.location.x() + (range + 1), self.location.y()),
             ),
             // Lower right
             Line::from_points(
-                &Point {
-                    x: self.location.x + (range + 1),
-                    y: self.location.y,
-                },
-                &Point {
-                    x: self.location.x,
-                    y: self.location.y + (range + 1),
-                },
+                &Point::new(self.location.x() + (range + 1), self.location.y()),
+                &Point::new(self.location.x(), self.location.y() + (range + 1)),
             ),
             // Lower left
             Line::from_points(
-                &Point {
-                    x: self.location.x,
-                    y: self.location.y + (range + 1),
-                },
-                &Point {
-                    x: self.location.x - (range + 1),
-                    y: self.location.y,
-                },
+                &Point::new(self.location.x(), self.location.y() + (range + 1)),
+                &Point::new(self.location.x() - (range + 1), self.location.y()),
             ),
         ]
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseSensorError;
-
-impl FromStr for Sensor {
-    type Err = ParseSensorError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split_s = s.split(&['=', ',', ' ', ':'][..]);
-
-        Ok(Self::new(
-            split_s
-                .nth(3)
-                .ok_or(ParseSensorError)?
-                .parse::<i32>()
-                .map_err(|_| ParseSensorError)?,
-            split_s
-                .nth(2)
-                .ok_or(ParseSensorError)?
-                .parse::<i32>()
-                .map_err(|_| ParseSensorError)?,
-            split_s
-                .nth(6)
-                .ok_or(ParseSensorError)?
-                .parse::<i32>()
-                .map_err(|_| ParseSensorError)?,
-            split_s
-                .nth(2)
-                .ok_or(ParseSensorError)?
-                .parse::<i32>()
-                .map_err(|_| ParseSensorError)?,
-        ))
-    }
+// `x=<x>, y=<y>`
+fn parse_point(parser: &mut Parser) -> Result<Point<i32>> {
+    let x = parser.key_value("x", Parser::number)?;
+    parser.literal(",")?;
+    let y = parser.key_value("y", Parser::number)?;
+    Ok(Point::new(x, y))
 }
 
-fn merge_ranges(mut ranges: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
-    ranges.sort_by_key(|range| range.0);
-
-    let mut merged_ranges = Vec::new();
-    if ranges.is_empty() {
-        return merged_ranges;
-    }
-
-    let mut current_range = ranges[0];
-    for range in ranges.iter().skip(1) {
-        if range.0 <= current_range.1 {
-            current_range.1 = cmp::max(current_range.1, range.1);
-        } else {
-            merged_ranges.push(current_range);
-            current_range = *range;
-        }
+impl Sensor {
+    fn parse(parser: &mut Parser) -> Result<Self> {
+        parser.literal("Sensor at")?;
+        let location = parse_point(parser)?;
+        parser.literal(": closest beacon is at")?;
+        let beacon = parse_point(parser)?;
+        Ok(Self::new(location, beacon))
     }
-    merged_ranges.push(current_range);
-
-    merged_ranges
 }
 
-fn part1_solver(input: &[String], y: i32) -> usize {
-    let sensors = input
+fn coverage_at_y(sensors: &[Sensor], y: i32) -> IntervalSet<i32> {
+    sensors
         .iter()
-        .map(|line| line.parse::<Sensor>().unwrap())
-        .collect::<Vec<Sensor>>();
+        .filter_map(|sensor| sensor.range_at_y(y))
+        .collect()
+}
 
-    let ranges = merge_ranges(
-        sensors
-            .iter()
-            .filter_map(|sensor| sensor.range_at_y(y))
-            .collect::<Vec<_>>(),
-    );
+pub fn parse(input: &str) -> Result<Vec<Sensor>> {
+    Parser::new(input).lines(Sensor::parse)
+}
+
+// The sensors together with the row and search area from the puzzle text,
+// which are much smaller for the sample than for the real input
+pub struct Survey {
+    sensors: Vec<Sensor>,
+    row: i32,
+    search_size: i32,
+}
 
-    let num_covered_points = ranges.iter().fold(0usize, |acc, range| {
-        acc + usize::try_from(range.1 - range.0 + 1).unwrap()
-    });
+pub fn part1(sensors: &[Sensor], y: i32) -> Result<usize> {
+    let num_covered_points = usize::try_from(coverage_at_y(sensors, y).len())
+        .map_err(|_| Error::new("too many positions are covered"))?;
 
     let beacons_on_same_line = sensors
         .iter()
-        .filter(|sensor| sensor.beacon.y == y)
-        .map(|sensor| sensor.beacon.x)
+        .filter(|sensor| sensor.beacon.y() == y)
+        .map(|sensor| sensor.beacon.x())
         .unique()
         .count();
 
-    num_covered_points - beacons_on_same_line
+    Ok(num_covered_points - beacons_on_same_line)
 }
 
-fn part2_solver(input: &[String], max_x: i32, max_y: i32) -> Option<u64> {
-    let sensors = input
-        .iter()
-        .map(|line| line.parse::<Sensor>().unwrap())
-        .collect::<Vec<Sensor>>();
-
+// Finds the only uncovered position with both coordinates from 0 to
+// `search_size`
+pub fn part2(sensors: &[Sensor], search_size: i32) -> Result<u64> {
     let mut lines: Vec<Line> = Vec::new();
 
     for sensor in sensors.iter() {
         lines.extend(sensor.get_lines());
     }
 
-    for (i, line1) in lines.iter().enumerate() {
-        for line2 in lines[(i + 1)..].iter() {
-            if let Some(intersection) = line1.intersection(line2) {
-                if 0 <= intersection.x
-                    && intersection.x <= max_x
-                    && 0 <= intersection.y
-                    && intersection.y <= max_y
-                    && !sensors
-                        .iter()
-                        .any(|sensor| sensor.covers(intersection.x, intersection.y))
-                {
-                    return Some(
-                        u64::try_from(intersection.x).unwrap() * 4000000
-                            + u64::try_from(intersection.y).unwrap(),
-                    );
-                }
-            }
+    // The uncovered position is just outside the range of several sensors,
+    // so it has to be on a row where the edges of two ranges intersect
+    let candidate_rows = lines
+        .iter()
+        .tuple_combinations()
+        .filter_map(|(line1, line2)| line1.intersection(line2))
+        .map(|intersection| intersection.y())
+        .filter(|y| (0..=search_size).contains(y))
+        .unique();
+
+    for y in candidate_rows {
+        if let Some(gap) = coverage_at_y(sensors, y).gaps(0..=search_size).next() {
+            return Ok(u64::try_from(*gap.start()).unwrap() * 4000000 + u64::try_from(y).unwrap());
         }
     }
-    None
+    Err(Error::new("no uncovered position found"))
 }
 
-pub fn part1(input: &[String]) -> usize {
-    part1_solver(input, 2000000)
-}
+pub struct Day15;
 
-pub fn part2(input: &[String]) -> u64 {
-    part2_solver(input, 4000000, 4000000).unwrap()
+impl Solution for Day15 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 15;
+
+    type Input<'a> = Survey;
+    type Output1 = usize;
+    type Output2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Survey> {
+        self.parse_with_params(input, &Params::default())
+    }
+
+    fn parse_with_params(&self, input: &str, params: &Params) -> Result<Survey> {
+        Ok(Survey {
+            sensors: parse(input)?,
+            row: params.get("row", 2000000)?,
+            search_size: params.get("search_size", 4000000)?,
+        })
+    }
+
+    fn part1(&self, survey: &Survey) -> Result<usize> {
+        part1(&survey.sensors, survey.row)
+    }
+
+    fn part2(&self, survey: &Survey) -> Result<u64> {
+        part2(&survey.sensors, survey.search_size)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(
-            part1_solver(&read_input_from_file("sample/day15.txt"), 10),
-            26
-        );
-        assert_eq!(part1(&read_input_from_file("input/day15.txt")), 5525990);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day15, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(
-            part2_solver(&read_input_from_file("sample/day15.txt"), 20, 20).unwrap(),
-            56000011
-        );
-        assert_eq!(
-            part2(&read_input_from_file("input/day15.txt")),
-            11756174628223
-        );
+    fn test_part2() -> Result<()> {
+        check_answers(&Day15, Part::Two)
     }
 }
@@ -1,6 +1,12 @@
-use std::{
-    cmp,
-    collections::{BinaryHeap, HashMap},
+use std::{cmp, collections::HashMap};
+
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::{
+        branch_and_bound::{BranchAndBound, Strategy},
+        parse::Parser,
+    },
 };
 
 const START_POINT: &str = "AA";
@@ -12,37 +18,37 @@ struct Valve {
     connections: Vec<usize>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseValveError;
-
-impl Valve {
-    fn from_str(s: &str, valve_to_index: &HashMap<&str, usize>) -> Result<Self, ParseValveError> {
-        let mut parts = s.split(&[' ', '=', ';', ','][..]);
-        let name = parts.nth(1).ok_or(ParseValveError)?;
-        let flowrate: usize = parts
-            .nth(3)
-            .ok_or(ParseValveError)?
-            .parse()
-            .map_err(|_| ParseValveError)?;
-
-        parts.nth(4);
+// A valve as it is described in the input, before the names are replaced by
+// indices
+struct ValveDescription<'a> {
+    line: usize,
+    name: &'a str,
+    flowrate: usize,
+    connections: Vec<&'a str>,
+}
 
-        Ok(Valve {
-            index: *valve_to_index.get(name).unwrap(),
+impl<'a> ValveDescription<'a> {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self> {
+        let line = parser.line();
+        parser.literal("Valve")?;
+        let name = parser.word()?;
+        let flowrate = parser.key_value("has flow rate", Parser::number)?;
+        parser.literal(";")?;
+        parser.one_of(&[
+            ("tunnels lead to valves", ()),
+            ("tunnel leads to valve", ()),
+        ])?;
+        let connections = parser.separated(",", Parser::word)?;
+        Ok(Self {
+            line,
+            name,
             flowrate,
-            connections: parts
-                .filter_map(|neighbor| {
-                    if neighbor.is_empty() {
-                        return None;
-                    }
-                    Some(*valve_to_index.get(neighbor).unwrap())
-                })
-                .collect(),
+            connections,
         })
     }
 }
 
-fn calculate_all_pairs(adj_matrix: &mut Vec<Vec<usize>>) {
+fn calculate_all_pairs(adj_matrix: &mut [Vec<usize>]) {
     for via in 0..adj_matrix.len() {
         for from in 0..adj_matrix.len() {
             for to in 0..adj_matrix.len() {
@@ -57,53 +63,16 @@ fn calculate_all_pairs(adj_matrix: &mut Vec<Vec<usize>>) {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 struct SearchState {
     pos1: usize,
     pos2: usize,
     flowrate: usize,
-    heuristic: usize,
     time_left1: usize,
     time_left2: usize,
     opened_valves: u16,
 }
 
-impl PartialOrd for SearchState {
-    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(other.heuristic.cmp(&self.heuristic))
-    }
-}
-
-impl Ord for SearchState {
-    fn cmp(&self, other: &Self) -> cmp::Ordering {
-        other.heuristic.cmp(&self.heuristic)
-    }
-}
-
-impl SearchState {
-    fn new(
-        pos1: usize,
-        pos2: usize,
-        flowrate: usize,
-        time_left1: usize,
-        time_left2: usize,
-        opened_valves: u16,
-        valves: &[Valve],
-    ) -> Self {
-        let mut s = Self {
-            pos1,
-            pos2,
-            flowrate,
-            heuristic: 0,
-            time_left1,
-            time_left2,
-            opened_valves,
-        };
-        s.heuristic = heuristic(valves, &s);
-        s
-    }
-}
-
 fn heuristic_helper(valves: &[Valve], search_state: &SearchState) -> usize {
     let mut best_flowrate = search_state.flowrate;
     let mut time_left1: i32 = i32::try_from(search_state.time_left1).unwrap() - 2;
@@ -181,17 +150,35 @@ fn heuristic(valves: &[Valve], state: &SearchState) -> usize {
     cmp::max(best_flowrate, heuristic_helper(valves, state))
 }
 
-fn get_valve_index_map(input: &[String]) -> HashMap<&str, usize> {
-    let mut ordered_valves = input
+// The valves that are worth opening, and the distances between them
+pub struct Tunnels {
+    valves: Vec<Valve>,
+    adj_matrix: Vec<Vec<usize>>,
+    start_index: usize,
+}
+
+fn get_valve_index_map<'a>(
+    descriptions: &[ValveDescription<'a>],
+) -> Result<HashMap<&'a str, usize>> {
+    let mut ordered_valves: Vec<(&str, usize)> = descriptions
         .iter()
-        .map(|line| {
-            let mut parts = line.split(&[' ', '=', ';', ','][..]);
-            (
-                parts.nth(1).unwrap(),
-                parts.nth(3).unwrap().parse::<usize>().unwrap(),
-            )
-        })
-        .collect::<Vec<_>>();
+        .map(|valve| (valve.name, valve.flowrate))
+        .collect();
+
+    if !ordered_valves.iter().any(|(name, _)| *name == START_POINT) {
+        return Err(Error::new(format!("there is no valve named {START_POINT}")));
+    }
+    // The opened valves are stored in a u16, which also has to fit the
+    // starting valve
+    if ordered_valves
+        .iter()
+        .filter(|(_, flowrate)| *flowrate != 0)
+        .count()
+        >= 16
+    {
+        return Err(Error::new("too many valves with a non-zero flow rate"));
+    }
+
     ordered_valves.sort_unstable_by(|a, b| {
         if a.1 == b.1 {
             if a.0 == START_POINT {
@@ -204,23 +191,39 @@ fn get_valve_index_map(input: &[String]) -> HashMap<&str, usize> {
         b.1.cmp(&a.1)
     });
 
-    ordered_valves
+    Ok(ordered_valves
         .into_iter()
         .enumerate()
         .map(|(i, (valve_identifier, _))| (valve_identifier, i))
-        .collect()
+        .collect())
 }
 
-fn parse_input(
-    input: &[String],
-    valve_to_index_map: &HashMap<&str, usize>,
-) -> (Vec<Valve>, Vec<Vec<usize>>) {
-    let mut valves: Vec<Valve> = input
+pub fn parse(input: &str) -> Result<Tunnels> {
+    let descriptions = Parser::new(input).lines(ValveDescription::parse)?;
+    let valve_to_index_map = get_valve_index_map(&descriptions)?;
+    let index_of = |valve: &ValveDescription, name: &str| {
+        valve_to_index_map
+            .get(name)
+            .copied()
+            .ok_or_else(|| Error::at_line(valve.line, format!("there is no valve named {name}")))
+    };
+
+    let mut valves: Vec<Valve> = descriptions
         .iter()
-        .map(|line| Valve::from_str(line, valve_to_index_map).unwrap())
-        .collect();
+        .map(|valve| {
+            Ok(Valve {
+                index: index_of(valve, valve.name)?,
+                flowrate: valve.flowrate,
+                connections: valve
+                    .connections
+                    .iter()
+                    .map(|name| index_of(valve, name))
+                    .collect::<Result<_>>()?,
+            })
+        })
+        .collect::<Result<_>>()?;
 
-    valves.sort_unstable_by(|a, b| a.index.cmp(&b.index));
+    valves.sort_unstable_by_key(|valve| valve.index);
 
     let mut big_adj_matrix: Vec<Vec<usize>> = vec![vec![usize::MAX; valves.len()]; valves.len()];
 
@@ -242,120 +245,128 @@ fn parse_input(
         }
     }
 
-    (valves, adj_matrix)
+    Ok(Tunnels {
+        valves,
+        adj_matrix,
+        start_index: valve_to_index_map[START_POINT],
+    })
 }
 
-fn search(
+// Opens one more valve with whoever has the most time left
+fn successors(
     valves: &[Valve],
     adj_matrix: &[Vec<usize>],
-    time_for_user_1: usize,
-    time_for_user_2: usize,
-    start_index: usize,
-) -> usize {
-    let mut queue: BinaryHeap<SearchState> = BinaryHeap::new();
-    queue.push(SearchState::new(
-        start_index,
-        start_index,
-        0,
-        time_for_user_1,
-        time_for_user_2,
-        0,
-        valves,
-    ));
+    state: &SearchState,
+) -> Vec<SearchState> {
+    let first = state.time_left1 >= state.time_left2;
+    let (pos, time_left) = if first {
+        (state.pos1, state.time_left1)
+    } else {
+        (state.pos2, state.time_left2)
+    };
+
+    valves
+        .iter()
+        .enumerate()
+        .filter(|(i, valve)| valve.flowrate != 0 && state.opened_valves & (1 << i) == 0)
+        .filter_map(|(i, valve)| {
+            // Valves that can't be reached are at a distance of `usize::MAX`
+            let time_left = time_left.checked_sub(adj_matrix[pos][valve.index].checked_add(1)?)?;
+            let mut next = SearchState {
+                flowrate: state.flowrate + valve.flowrate * time_left,
+                opened_valves: state.opened_valves | (1 << i),
+                ..*state
+            };
+            if first {
+                next.pos1 = valve.index;
+                next.time_left1 = time_left;
+            } else {
+                next.pos2 = valve.index;
+                next.time_left2 = time_left;
+            }
+            Some(next)
+        })
+        .collect()
+}
 
-    let mut best_result = 0usize;
+fn search(tunnels: &Tunnels, time_for_user_1: usize, time_for_user_2: usize) -> usize {
+    let Tunnels {
+        valves,
+        adj_matrix,
+        start_index,
+    } = tunnels;
+    let start = SearchState {
+        pos1: *start_index,
+        pos2: *start_index,
+        flowrate: 0,
+        time_left1: time_for_user_1,
+        time_left2: time_for_user_2,
+        opened_valves: 0,
+    };
+
+    let (_, best_result) = BranchAndBound::new(Strategy::BestFirst).maximize(
+        start,
+        |state| successors(valves, adj_matrix, state),
+        |state| heuristic(valves, state),
+        |state| state.flowrate,
+    );
+    best_result
+}
 
-    while let Some(state) = queue.pop() {
-        best_result = cmp::max(best_result, state.flowrate);
-        if best_result >= state.heuristic || (state.time_left1 <= 1 && state.time_left2 <= 1) {
-            continue;
-        }
+pub fn part1(tunnels: &Tunnels) -> Result<usize> {
+    Ok(search(tunnels, 30, 0))
+}
 
-        if state.time_left1 >= state.time_left2 {
-            for (i, valve) in valves.iter().enumerate() {
-                if valve.flowrate != 0 && state.opened_valves & (1 << i) == 0 {
-                    let time_needed = adj_matrix[state.pos1][valve.index] + 1;
-
-                    if time_needed <= state.time_left1 {
-                        let new_state = SearchState::new(
-                            valve.index,
-                            state.pos2,
-                            state.flowrate + (valve.flowrate * (state.time_left1 - time_needed)),
-                            state.time_left1 - time_needed,
-                            state.time_left2,
-                            state.opened_valves | (1 << i),
-                            valves,
-                        );
-
-                        if new_state.heuristic > best_result {
-                            queue.push(new_state);
-                        }
-                    }
-                }
-            }
-        } else {
-            for (i, valve) in valves.iter().enumerate() {
-                if valve.flowrate != 0 && state.opened_valves & (1 << i) == 0 {
-                    let action_time = adj_matrix[state.pos2][valve.index] + 1;
-
-                    if action_time <= state.time_left2 {
-                        let new_state = SearchState::new(
-                            state.pos1,
-                            valve.index,
-                            state.flowrate + (valve.flowrate * (state.time_left2 - action_time)),
-                            state.time_left1,
-                            state.time_left2 - action_time,
-                            state.opened_valves | (1 << i),
-                            valves,
-                        );
-
-                        if new_state.heuristic > best_result {
-                            queue.push(new_state);
-                        }
-                    }
-                }
-            }
-        }
-    }
-    best_result
+pub fn part2(tunnels: &Tunnels) -> Result<usize> {
+    Ok(search(tunnels, 26, 26))
 }
 
-fn solve(input: &[String], time_for_user_1: usize, time_for_user_2: usize) -> usize {
-    let valve_to_index_map = get_valve_index_map(input);
+pub struct Day16;
 
-    let (valves, adj_matrix) = parse_input(input, &valve_to_index_map);
+impl Solution for Day16 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 16;
 
-    search(
-        &valves,
-        &adj_matrix,
-        time_for_user_1,
-        time_for_user_2,
-        valve_to_index_map[START_POINT],
-    )
-}
+    type Input<'a> = Tunnels;
+    type Output1 = usize;
+    type Output2 = usize;
 
-pub fn part1(input: &[String]) -> usize {
-    solve(input, 30, 0)
-}
+    fn parse(&self, input: &str) -> Result<Tunnels> {
+        parse(input)
+    }
+
+    fn part1(&self, tunnels: &Tunnels) -> Result<usize> {
+        part1(tunnels)
+    }
 
-pub fn part2(input: &[String]) -> usize {
-    solve(input, 26, 26)
+    fn part2(&self, tunnels: &Tunnels) -> Result<usize> {
+        part2(tunnels)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
+
+    #[test]
+    fn test_unreachable_valve() -> Result<()> {
+        let tunnels = parse(
+            "Valve AA has flow rate=0; tunnels lead to valves BB\n\
+             Valve BB has flow rate=5; tunnels lead to valves AA\n\
+             Valve CC has flow rate=7; tunnels lead to valves CC\n",
+        )?;
+        assert_eq!(part1(&tunnels)?, 5 * 28);
+        Ok(())
+    }
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day16.txt")), 1651);
-        assert_eq!(part1(&read_input_from_file("input/day16.txt")), 1944);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day16, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day16.txt")), 1707);
-        assert_eq!(part2(&read_input_from_file("input/day16.txt")), 2679);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day16, Part::Two)
     }
 }
@@ -1,13 +1,16 @@
-use std::{
-    cmp,
-    collections::{HashMap, HashSet},
-    fmt,
-};
+use std::{cmp, collections::HashSet};
 
-use crate::util::Point;
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::{cycle, Grid, Point},
+    visualize::{NoObserver, Observer, Palette},
+};
 
-const PART_1_MAX_ITERATIONS: u64 = 2022;
-const PART_2_MAX_ITERATIONS: u64 = 1000000000000;
+const PART_1_MAX_ITERATIONS: usize = 2022;
+const PART_2_MAX_ITERATIONS: usize = 1000000000000;
+// The number of rows at the top of the tower that are shown in a frame
+const FRAME_ROWS: usize = 40;
 
 #[derive(Debug)]
 struct Tower {
@@ -24,13 +27,13 @@ impl Tower {
             highest_blocks: [-1; Self::WIDTH],
         };
         for x in 0..Self::WIDTH {
-            res.blocks.insert(Point { x: x as i64, y: -1 });
+            res.blocks.insert(Point::new(x as i64, -1));
         }
         res
     }
 
     fn is_within(&self, pos: &Point<i64>) -> bool {
-        pos.y >= 0 && 0 <= pos.x && pos.x < Self::WIDTH as i64
+        pos.y() >= 0 && 0 <= pos.x() && pos.x() < Self::WIDTH as i64
     }
 
     fn is_empty(&self, pos: &Point<i64>) -> bool {
@@ -43,7 +46,8 @@ impl Tower {
 
     fn add_block(&mut self, pos: &Point<i64>) {
         self.blocks.insert(*pos);
-        self.highest_blocks[pos.x as usize] = cmp::max(self.highest_blocks[pos.x as usize], pos.y);
+        self.highest_blocks[pos.x() as usize] =
+            cmp::max(self.highest_blocks[pos.x() as usize], pos.y());
     }
 
     fn get_relative_heights(&self) -> [i64; Self::WIDTH] {
@@ -55,26 +59,37 @@ impl Tower {
         }
         res
     }
-}
 
-impl fmt::Display for Tower {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let tower_height = self.get_height();
-        for y in (0..tower_height).rev() {
-            write!(f, "|")?;
+    // Draws the top `max_rows` rows of the tower, and the floor if it is
+    // within them
+    fn draw(&self, max_rows: usize) -> Grid<char> {
+        let height = self.get_height();
+        let num_rows = cmp::min(height as usize, max_rows);
+        let mut grid = Grid::new(Self::WIDTH + 2, num_rows + 1, '.');
+
+        for row in 0..num_rows {
+            let y = height - 1 - row as i64;
             for x in 0..Self::WIDTH {
-                if self.blocks.contains(&Point { x: x as i64, y }) {
-                    write!(f, "#")?;
-                } else {
-                    write!(f, ".")?;
+                if self.blocks.contains(&Point::new(x as i64, y)) {
+                    grid[Point::new(x as i32 + 1, row as i32)] = '#';
                 }
             }
-            writeln!(f, "|")?;
         }
-        for _ in 0..Self::WIDTH + 2 {
-            write!(f, "-")?;
+        for y in 0..=num_rows {
+            grid[Point::new(0, y as i32)] = '|';
+            grid[Point::new(Self::WIDTH as i32 + 1, y as i32)] = '|';
         }
-        Ok(())
+
+        let bottom = num_rows as i32;
+        let floor = if num_rows == height as usize {
+            '-'
+        } else {
+            '~'
+        };
+        for x in 0..Self::WIDTH + 2 {
+            grid[Point::new(x as i32, bottom)] = floor;
+        }
+        grid
     }
 }
 
@@ -85,16 +100,16 @@ struct Rock {
 }
 
 impl Rock {
-    const NUM_PIECES: u64 = 5;
+    const NUM_PIECES: usize = 5;
 
     #[rustfmt::skip]
-    fn get_next_rock(index: u64) -> Rock {
+    fn get_next_rock(index: usize) -> Rock {
         match index % Self::NUM_PIECES {
-            0 => Rock{ num_blocks: 4, blocks: [Point{x: 0, y: 0}, Point{x: 1, y: 0}, Point{x: 2, y: 0}, Point{x: 3, y: 0}, Point{x: 0, y: 0}]},
-            1 => Rock{ num_blocks: 5, blocks: [Point{x: 1, y: 0}, Point{x: 0, y: 1}, Point{x: 1, y: 1}, Point{x: 2, y: 1}, Point{x: 1, y: 2}]},
-            2 => Rock{ num_blocks: 5, blocks: [Point{x: 0, y: 0}, Point{x: 1, y: 0}, Point{x: 2, y: 0}, Point{x: 2, y: 1}, Point{x: 2, y: 2}]},
-            3 => Rock{ num_blocks: 4, blocks: [Point{x: 0, y: 0}, Point{x: 0, y: 1}, Point{x: 0, y: 2}, Point{x: 0, y: 3}, Point{x: 0, y: 0}]},
-            4 => Rock{ num_blocks: 4, blocks: [Point{x: 0, y: 0}, Point{x: 1, y: 0}, Point{x: 0, y: 1}, Point{x: 1, y: 1}, Point{x: 0, y: 0}]},
+            0 => Rock{ num_blocks: 4, blocks: [Point::new(0, 0), Point::new(1, 0), Point::new(2, 0), Point::new(3, 0), Point::new(0, 0)]},
+            1 => Rock{ num_blocks: 5, blocks: [Point::new(1, 0), Point::new(0, 1), Point::new(1, 1), Point::new(2, 1), Point::new(1, 2)]},
+            2 => Rock{ num_blocks: 5, blocks: [Point::new(0, 0), Point::new(1, 0), Point::new(2, 0), Point::new(2, 1), Point::new(2, 2)]},
+            3 => Rock{ num_blocks: 4, blocks: [Point::new(0, 0), Point::new(0, 1), Point::new(0, 2), Point::new(0, 3), Point::new(0, 0)]},
+            4 => Rock{ num_blocks: 4, blocks: [Point::new(0, 0), Point::new(1, 0), Point::new(0, 1), Point::new(1, 1), Point::new(0, 0)]},
             _ => panic!("Invalid index"),
         }
     }
@@ -102,39 +117,38 @@ impl Rock {
 
 #[derive(Eq, Hash, PartialEq, Debug)]
 struct PatternMatchState {
-    i_piece_cycle: u64,
-    i_wind_cycle: u64,
+    i_piece_cycle: usize,
+    i_wind_cycle: usize,
     heights: [i64; Tower::WIDTH],
 }
 
-fn solve(input: &[String], max_num_rocks: u64) -> u64 {
-    let mut wind_iter = input[0].chars().cycle();
-    let mut tower = Tower::new();
-
-    let mut prev_states: HashMap<PatternMatchState, (u64, i64)> = HashMap::new();
-    let mut cycle_added_length: u64 = 0;
-    let mut cycle_found = false;
+struct Simulation {
+    tower: Tower,
+    wind: Vec<Point<i64>>,
+    rock_index: usize,
+    wind_index: usize,
+}
 
-    let mut rock_index: u64 = 0;
-    let mut wind_index: u64 = 0;
-    let wind_cycle_length = input[0].len() as u64;
+impl Simulation {
+    fn new(wind: Vec<Point<i64>>) -> Self {
+        Self {
+            tower: Tower::new(),
+            wind,
+            rock_index: 0,
+            wind_index: 0,
+        }
+    }
 
-    while rock_index < max_num_rocks {
-        let rock = Rock::get_next_rock(rock_index);
+    fn drop_rock(&mut self) {
+        let rock = Rock::get_next_rock(self.rock_index);
+        let tower = &mut self.tower;
 
-        let mut rock_pos = Point {
-            x: 2,
-            y: tower.get_height() + 3,
-        };
+        let mut rock_pos = Point::new(2, tower.get_height() + 3);
 
         let mut done_falling = false;
         while !done_falling {
-            wind_index += 1;
-            let horizontal_movement = match wind_iter.next().unwrap() {
-                '<' => Point { x: -1, y: 0 },
-                '>' => Point { x: 1, y: 0 },
-                _ => panic!("Invalid input"),
-            };
+            let horizontal_movement = self.wind[self.wind_index % self.wind.len()];
+            self.wind_index += 1;
 
             if rock.blocks.iter().all(|p| {
                 let tmp_pos = rock_pos + *p + horizontal_movement;
@@ -143,7 +157,7 @@ fn solve(input: &[String], max_num_rocks: u64) -> u64 {
                 rock_pos += horizontal_movement
             }
 
-            let vertical_movement = Point { x: 0, y: -1 };
+            let vertical_movement = Point::new(0, -1);
 
             if rock.blocks[0..rock.num_blocks]
                 .iter()
@@ -158,63 +172,122 @@ fn solve(input: &[String], max_num_rocks: u64) -> u64 {
         for p in rock.blocks[0..rock.num_blocks].iter() {
             tower.add_block(&(rock_pos + *p));
         }
+        self.rock_index += 1;
+    }
+
+    fn pattern_match_state(&self) -> PatternMatchState {
+        PatternMatchState {
+            i_piece_cycle: self.rock_index % Rock::NUM_PIECES,
+            i_wind_cycle: self.wind_index % self.wind.len(),
+            heights: self.tower.get_relative_heights(),
+        }
+    }
+}
 
-        if !cycle_found {
-            let pattern_match_state = PatternMatchState {
-                i_piece_cycle: rock_index % Rock::NUM_PIECES,
-                i_wind_cycle: wind_index % wind_cycle_length,
-                heights: tower.get_relative_heights(),
-            };
+// Returns the direction that each jet pushes in
+pub fn parse(input: &str) -> Result<Vec<Point<i64>>> {
+    let line = input
+        .lines()
+        .next()
+        .ok_or_else(|| Error::new("input is empty"))?;
+    if line.is_empty() {
+        return Err(Error::at_line(1, "no jet pattern given"));
+    }
+    line.chars()
+        .map(|c| match c {
+            '<' => Ok(Point::new(-1, 0)),
+            '>' => Ok(Point::new(1, 0)),
+            _ => Err(Error::at_line(1, format!("invalid jet direction '{c}'"))),
+        })
+        .collect()
+}
 
-            let prev_state =
-                prev_states.insert(pattern_match_state, (rock_index, tower.get_height()));
+fn solve(wind: &[Point<i64>], max_num_rocks: usize, observer: &mut dyn Observer) -> Result<u64> {
+    let simulation = Simulation::new(wind.to_vec());
+
+    let height = cycle::extrapolate(
+        simulation,
+        max_num_rocks,
+        |simulation| {
+            simulation.drop_rock();
+            observer.show(|| simulation.tower.draw(FRAME_ROWS));
+        },
+        Simulation::pattern_match_state,
+        |simulation| simulation.tower.get_height(),
+    );
+    Ok(u64::try_from(height).unwrap())
+}
 
-            if let Some((prev_index, prev_height)) = prev_state {
-                let cycle_len = rock_index - prev_index;
-                let cycle_height = tower.get_height() - prev_height;
-                let remaining_cycles = (max_num_rocks - rock_index - 1) / cycle_len;
+pub fn part1(wind: &[Point<i64>], observer: &mut dyn Observer) -> Result<u64> {
+    solve(wind, PART_1_MAX_ITERATIONS, observer)
+}
 
-                cycle_added_length = remaining_cycles * u64::try_from(cycle_height).unwrap();
+pub fn part2(wind: &[Point<i64>], observer: &mut dyn Observer) -> Result<u64> {
+    solve(wind, PART_2_MAX_ITERATIONS, observer)
+}
 
-                rock_index += remaining_cycles * cycle_len;
-                cycle_found = true;
-            }
-        }
+pub struct Day17;
+
+impl Solution for Day17 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 17;
+
+    type Input<'a> = Vec<Point<i64>>;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    const PALETTE: Palette = Palette {
+        background: [0, 0, 0],
+        colors: &[
+            ('.', [0, 0, 0]),
+            ('#', [250, 130, 30]),
+            ('|', [90, 90, 90]),
+            ('-', [90, 90, 90]),
+            ('~', [50, 50, 50]),
+        ],
+    };
+
+    fn parse(&self, input: &str) -> Result<Vec<Point<i64>>> {
+        parse(input)
+    }
 
-        rock_index += 1;
+    fn part1(&self, wind: &Vec<Point<i64>>) -> Result<u64> {
+        part1(wind, &mut NoObserver)
     }
 
-    u64::try_from(tower.get_height()).unwrap() + cycle_added_length
-}
+    fn part2(&self, wind: &Vec<Point<i64>>) -> Result<u64> {
+        part2(wind, &mut NoObserver)
+    }
 
-pub fn part1(input: &[String]) -> u64 {
-    solve(input, PART_1_MAX_ITERATIONS)
-}
+    fn visualize_part1(
+        &self,
+        wind: &Vec<Point<i64>>,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<u64>> {
+        Some(part1(wind, observer))
+    }
 
-pub fn part2(input: &[String]) -> u64 {
-    solve(input, PART_2_MAX_ITERATIONS)
+    fn visualize_part2(
+        &self,
+        wind: &Vec<Point<i64>>,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<u64>> {
+        Some(part2(wind, observer))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day17.txt")), 3068);
-        assert_eq!(part1(&read_input_from_file("input/day17.txt")), 3209);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day17, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(
-            part2(&read_input_from_file("sample/day17.txt")),
-            1514285714288
-        );
-        assert_eq!(
-            part2(&read_input_from_file("input/day17.txt")),
-            1580758017509
-        );
+    fn test_part2() -> Result<()> {
+        check_answers(&Day17, Part::Two)
     }
 }
@@ -1,174 +1,70 @@
-use std::{collections::VecDeque, str::FromStr};
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::{Voxel, Voxels},
+};
 
-#[derive(Debug, Clone, Copy)]
-struct Cube {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-impl FromStr for Cube {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut it = s.split(',');
-        let x = it.next().unwrap().parse::<i32>().unwrap() + 1;
-        let y = it.next().unwrap().parse::<i32>().unwrap() + 1;
-        let z = it.next().unwrap().parse::<i32>().unwrap() + 1;
-        Ok(Cube { x, y, z })
-    }
+pub fn parse(input: &str) -> Result<Voxels> {
+    let cubes = input
+        .lines()
+        .enumerate()
+        .map(|(i, s)| {
+            s.parse::<Voxel>()
+                .map_err(|_| Error::at_line(i + 1, format!("invalid cube \"{s}\"")))
+        })
+        .collect::<Result<Vec<Voxel>>>()?;
+    Voxels::new(&cubes)
 }
 
-impl Cube {
-    fn get_neighbors(&self) -> [Cube; 6] {
-        [
-            Cube {
-                x: self.x - 1,
-                y: self.y,
-                z: self.z,
-            },
-            Cube {
-                x: self.x + 1,
-                y: self.y,
-                z: self.z,
-            },
-            Cube {
-                x: self.x,
-                y: self.y - 1,
-                z: self.z,
-            },
-            Cube {
-                x: self.x,
-                y: self.y + 1,
-                z: self.z,
-            },
-            Cube {
-                x: self.x,
-                y: self.y,
-                z: self.z - 1,
-            },
-            Cube {
-                x: self.x,
-                y: self.y,
-                z: self.z + 1,
-            },
-        ]
-    }
+pub fn part1(droplet: &Voxels) -> Result<usize> {
+    Ok(droplet.surface_area())
 }
 
-struct Grid {
-    arr: [[[bool; Self::GRID_SIZE]; Self::GRID_SIZE]; Self::GRID_SIZE],
+// Only the faces that the air outside of the droplet touches
+pub fn part2(droplet: &Voxels) -> Result<usize> {
+    Ok(droplet.exterior_surface_area())
 }
 
-impl Grid {
-    const GRID_SIZE: usize = 22;
-
-    fn new() -> Self {
-        Self {
-            arr: [[[false; Self::GRID_SIZE]; Self::GRID_SIZE]; Self::GRID_SIZE],
-        }
-    }
+pub struct Day18;
 
-    fn is_within(cube: &Cube) -> bool {
-        let upper = i32::try_from(Self::GRID_SIZE).unwrap();
-        0 <= cube.x
-            && cube.x < upper
-            && 0 <= cube.y
-            && cube.y < upper
-            && 0 <= cube.z
-            && cube.z < upper
-    }
+impl Solution for Day18 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 18;
 
-    fn get_cell(&self, cube: &Cube) -> bool {
-        self.arr[usize::try_from(cube.x).unwrap()][usize::try_from(cube.y).unwrap()]
-            [usize::try_from(cube.z).unwrap()]
-    }
+    type Input<'a> = Voxels;
+    type Output1 = usize;
+    type Output2 = usize;
 
-    fn set_cell(&mut self, cube: &Cube, val: bool) {
-        self.arr[usize::try_from(cube.x).unwrap()][usize::try_from(cube.y).unwrap()]
-            [usize::try_from(cube.z).unwrap()] = val;
+    fn parse(&self, input: &str) -> Result<Voxels> {
+        parse(input)
     }
 
-    fn get_num_neighbors(&self, cube: &Cube) -> usize {
-        let mut count = 0;
-        for neighbor in cube.get_neighbors().iter() {
-            if Self::is_within(neighbor) && self.get_cell(neighbor) {
-                count += 1;
-            }
-        }
-        count
+    fn part1(&self, droplet: &Voxels) -> Result<usize> {
+        part1(droplet)
     }
 
-    fn calculate_accessible_cells(&self) -> usize {
-        let mut exposed_faces = 0usize;
-
-        let mut visited = Grid::new();
-        let mut q = VecDeque::<Cube>::new();
-        q.push_back(Cube { x: 0, y: 0, z: 0 });
-
-        while let Some(cube) = q.pop_front() {
-            if self.get_cell(&cube) {
-                exposed_faces += 1;
-            } else if !visited.get_cell(&cube) {
-                visited.set_cell(&cube, true);
-                for neighbor in cube.get_neighbors().iter() {
-                    if Self::is_within(neighbor) {
-                        q.push_back(*neighbor);
-                    }
-                }
-            }
-        }
-        exposed_faces
+    fn part2(&self, droplet: &Voxels) -> Result<usize> {
+        part2(droplet)
     }
 }
 
-pub fn part1(input: &[String]) -> usize {
-    let mut grid = Grid::new();
-
-    let cubes = input
-        .iter()
-        .map(|s| s.parse::<Cube>().unwrap())
-        .collect::<Vec<Cube>>();
-
-    cubes.iter().for_each(|cube| {
-        grid.set_cell(cube, true);
-    });
-
-    cubes.iter().fold(0, |mut acc, cube| {
-        acc += 6 - grid.get_num_neighbors(cube);
-        acc
-    })
-}
-
-pub fn part2(input: &[String]) -> usize {
-    let mut grid = Grid::new();
-
-    let cubes = input
-        .iter()
-        .map(|s| s.parse::<Cube>().unwrap())
-        .collect::<Vec<Cube>>();
-
-    cubes.iter().for_each(|cube| {
-        grid.set_cell(cube, true);
-    });
-
-    grid.calculate_accessible_cells()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
+
+    #[test]
+    fn test_too_large() {
+        assert!(parse("0,0,0\n2000000000,0,0").is_err());
+    }
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day18.txt")), 64);
-        assert_eq!(part1(&read_input_from_file("input/day18.txt")), 3448);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day18, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day18.txt")), 58);
-        assert_eq!(part2(&read_input_from_file("input/day18.txt")), 2052);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day18, Part::Two)
     }
 }
@@ -4,7 +4,11 @@ use std::{
     thread::{self, JoinHandle},
 };
 
-const DEBUG_PRINT: bool = false;
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::branch_and_bound::{BranchAndBound, Strategy},
+};
 
 #[derive(Debug, Clone)]
 struct Resources {
@@ -51,7 +55,7 @@ impl SubAssign<&Resources> for Resources {
 }
 
 #[derive(Debug, Clone)]
-struct BlueprintCosts {
+pub struct BlueprintCosts {
     ore: Resources,
     clay: Resources,
     obsidian: Resources,
@@ -115,99 +119,92 @@ fn heuristic2(state: &SearchState, blueprint_costs: &BlueprintCosts) -> usize {
     res.geode
 }
 
+fn successors(
+    state: &SearchState,
+    costs: &BlueprintCosts,
+    max_costs: &Resources,
+) -> Vec<SearchState> {
+    let mut next_states = Vec::new();
+    if state.time == 1 {
+        return next_states;
+    }
+
+    let mut build = |cost: &Resources, add_robot: fn(&mut Resources)| {
+        let mut new_state = state.clone();
+        new_state.resources -= cost;
+        new_state.resources += &new_state.production;
+        add_robot(&mut new_state.production);
+        new_state.time -= 1;
+        next_states.push(new_state);
+    };
+
+    // Waits without building a robot
+    build(&Resources::new(), |_| {});
+
+    // Building a robot only helps if its resources can still be spent, and
+    // never more robots than the most that can be spent in one minute
+    if state.time >= 4 + costs.ore.ore
+        && state.production.ore < max_costs.ore
+        && state.resources.can_afford(&costs.ore)
+    {
+        build(&costs.ore, |production| production.ore += 1);
+    }
+    if state.time >= 6
+        && state.production.clay < max_costs.clay
+        && state.resources.can_afford(&costs.clay)
+    {
+        build(&costs.clay, |production| production.clay += 1);
+    }
+    if state.time >= 4
+        && state.production.obsidian < max_costs.obsidian
+        && state.resources.can_afford(&costs.obsidian)
+    {
+        build(&costs.obsidian, |production| production.obsidian += 1);
+    }
+    if state.resources.can_afford(&costs.geode) {
+        build(&costs.geode, |production| production.geode += 1);
+    }
+    next_states
+}
+
+fn max_geodes(costs: &BlueprintCosts, num_minutes: usize) -> usize {
+    let max_costs = Resources {
+        ore: cmp::max(
+            cmp::max(costs.ore.ore, costs.clay.ore),
+            cmp::max(costs.obsidian.ore, costs.geode.ore),
+        ),
+        clay: costs.obsidian.clay,
+        obsidian: costs.geode.obsidian,
+        geode: 0,
+    };
+
+    let start = SearchState {
+        time: num_minutes,
+        resources: Resources::new(),
+        production: Resources {
+            ore: 1,
+            clay: 0,
+            obsidian: 0,
+            geode: 0,
+        },
+    };
+
+    let (_, max_score) = BranchAndBound::new(Strategy::DepthFirst).maximize(
+        start,
+        |state| successors(state, costs, &max_costs),
+        |state| heuristic2(state, costs),
+        // The geodes at the end if no more robots are built
+        |state| state.resources.geode + state.production.geode * state.time,
+    );
+    max_score
+}
+
 fn simulate_robot_production(blueprints: &[BlueprintCosts], num_minutes: usize) -> Vec<usize> {
     let mut threads: Vec<JoinHandle<usize>> = Vec::new();
 
-    for (i, blueprint) in blueprints.iter().enumerate() {
+    for blueprint in blueprints {
         let costs = blueprint.clone();
-        threads.push(thread::spawn(move || {
-            let max_costs = Resources {
-                ore: cmp::max(
-                    cmp::max(costs.ore.ore, costs.clay.ore),
-                    cmp::max(costs.obsidian.ore, costs.geode.ore),
-                ),
-                clay: costs.obsidian.clay,
-                obsidian: costs.geode.obsidian,
-                geode: 0,
-            };
-
-            let mut max_score: usize = 0;
-            let mut stack: Vec<SearchState> = Vec::new();
-            stack.push(SearchState {
-                time: num_minutes,
-                resources: Resources::new(),
-                production: Resources {
-                    ore: 1,
-                    clay: 0,
-                    obsidian: 0,
-                    geode: 0,
-                },
-            });
-
-            while let Some(state) = stack.pop() {
-                if state.time == 1 {
-                    max_score = cmp::max(max_score, state.resources.geode + state.production.geode);
-                    continue;
-                }
-
-                if heuristic2(&state, &costs) <= max_score {
-                    continue;
-                }
-
-                let mut new_state = state.clone();
-                new_state.resources += &new_state.production;
-                new_state.time -= 1;
-                stack.push(new_state);
-
-                // TODO: Cleanup
-                if state.time >= 4 + costs.ore.ore
-                    && state.production.ore < max_costs.ore
-                    && state.resources.can_afford(&costs.ore)
-                {
-                    let mut new_state = state.clone();
-                    new_state.resources -= &costs.ore;
-                    new_state.resources += &new_state.production;
-                    new_state.production.ore += 1;
-                    new_state.time -= 1;
-                    stack.push(new_state);
-                }
-                if state.time >= 6
-                    && state.production.clay < max_costs.clay
-                    && state.resources.can_afford(&costs.clay)
-                {
-                    let mut new_state = state.clone();
-                    new_state.resources -= &costs.clay;
-                    new_state.resources += &new_state.production;
-                    new_state.production.clay += 1;
-                    new_state.time -= 1;
-                    stack.push(new_state);
-                }
-                if state.time >= 4
-                    && state.production.obsidian < max_costs.obsidian
-                    && state.resources.can_afford(&costs.obsidian)
-                {
-                    let mut new_state = state.clone();
-                    new_state.resources -= &costs.obsidian;
-                    new_state.resources += &new_state.production;
-                    new_state.production.obsidian += 1;
-                    new_state.time -= 1;
-                    stack.push(new_state);
-                }
-                if state.resources.can_afford(&costs.geode) {
-                    let mut new_state = state.clone();
-                    new_state.resources -= &costs.geode;
-                    new_state.resources += &new_state.production;
-                    new_state.production.geode += 1;
-                    new_state.time -= 1;
-                    stack.push(new_state);
-                }
-            }
-            if DEBUG_PRINT {
-                println!("Max score of iter {} is {}", i + 1, max_score);
-            }
-
-            max_score
-        }));
+        threads.push(thread::spawn(move || max_geodes(&costs, num_minutes)));
     }
 
     let mut result: Vec<usize> = Vec::new();
@@ -217,53 +214,89 @@ fn simulate_robot_production(blueprints: &[BlueprintCosts], num_minutes: usize)
     result
 }
 
-fn parse_blueprints(input: &[String]) -> Vec<BlueprintCosts> {
+pub fn parse(input: &str) -> Result<Vec<BlueprintCosts>> {
     input
-        .iter()
-        .map(|line| {
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
             let mut split_line = line.split(' ');
+            let mut next_cost = |n: usize| {
+                split_line
+                    .nth(n)
+                    .and_then(|cost| cost.parse::<usize>().ok())
+                    .ok_or_else(|| Error::at_line(i + 1, format!("invalid blueprint \"{line}\"")))
+            };
 
             let mut costs = BlueprintCosts::new();
-            costs.ore.ore = str::parse::<usize>(split_line.nth(6).unwrap()).unwrap();
-            costs.clay.ore = str::parse::<usize>(split_line.nth(5).unwrap()).unwrap();
-            costs.obsidian.ore = str::parse::<usize>(split_line.nth(5).unwrap()).unwrap();
-            costs.obsidian.clay = str::parse::<usize>(split_line.nth(2).unwrap()).unwrap();
-            costs.geode.ore = str::parse::<usize>(split_line.nth(5).unwrap()).unwrap();
-            costs.geode.obsidian = str::parse::<usize>(split_line.nth(2).unwrap()).unwrap();
-            costs
+            costs.ore.ore = next_cost(6)?;
+            costs.clay.ore = next_cost(5)?;
+            costs.obsidian.ore = next_cost(5)?;
+            costs.obsidian.clay = next_cost(2)?;
+            costs.geode.ore = next_cost(5)?;
+            costs.geode.obsidian = next_cost(2)?;
+            Ok(costs)
         })
         .collect()
 }
 
 // TODO: Make this more clean
-fn solve(input: &[String], num_blueprints: isize, num_minutes: usize) -> Vec<usize> {
-    let blueprints = parse_blueprints(input);
+fn solve(
+    blueprints: &[BlueprintCosts],
+    num_blueprints: isize,
+    num_minutes: usize,
+) -> Result<Vec<usize>> {
     if num_blueprints == -1 || num_blueprints >= blueprints.len().try_into().unwrap() {
-        simulate_robot_production(&blueprints, num_minutes)
+        Ok(simulate_robot_production(blueprints, num_minutes))
     } else {
-        simulate_robot_production(&blueprints[..(num_blueprints as usize)], num_minutes)
+        Ok(simulate_robot_production(
+            &blueprints[..(num_blueprints as usize)],
+            num_minutes,
+        ))
     }
 }
 
-pub fn part1(input: &[String]) -> usize {
-    let scores = solve(input, -1, 24);
+pub fn part1(blueprints: &[BlueprintCosts]) -> Result<usize> {
+    let scores = solve(blueprints, -1, 24)?;
 
-    scores
+    Ok(scores
         .into_iter()
         .enumerate()
         .map(|(i, score)| (i + 1) * score)
-        .sum()
+        .sum())
 }
 
-pub fn part2(input: &[String]) -> usize {
-    let scores = solve(input, 3, 32);
-    scores.into_iter().reduce(|res, score| res * score).unwrap()
+pub fn part2(blueprints: &[BlueprintCosts]) -> Result<usize> {
+    let scores = solve(blueprints, 3, 32)?;
+    Ok(scores.into_iter().product())
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 19;
+
+    type Input<'a> = Vec<BlueprintCosts>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<BlueprintCosts>> {
+        parse(input)
+    }
+
+    fn part1(&self, blueprints: &Vec<BlueprintCosts>) -> Result<usize> {
+        part1(blueprints)
+    }
+
+    fn part2(&self, blueprints: &Vec<BlueprintCosts>) -> Result<usize> {
+        part2(blueprints)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
     fn test_heuristic() {
@@ -303,14 +336,12 @@ mod tests {
     }
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day19.txt")), 33);
-        assert_eq!(part1(&read_input_from_file("input/day19.txt")), 1365);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day19, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day19.txt")), 56 * 62);
-        assert_eq!(part2(&read_input_from_file("input/day19.txt")), 4864);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day19, Part::Two)
     }
 }
@@ -1,3 +1,8 @@
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
 #[derive(PartialEq)]
 enum Move {
     Rock,
@@ -5,15 +10,15 @@ enum Move {
     Scissors,
 }
 impl Move {
-    fn from_char(c: &char) -> Move {
+    fn from_char(c: &char) -> Option<Move> {
         match c {
-            'A' => Move::Rock,
-            'B' => Move::Paper,
-            'C' => Move::Scissors,
-            'X' => Move::Rock,
-            'Y' => Move::Paper,
-            'Z' => Move::Scissors,
-            _ => panic!("Invalid input: Move not in list"),
+            'A' => Some(Move::Rock),
+            'B' => Some(Move::Paper),
+            'C' => Some(Move::Scissors),
+            'X' => Some(Move::Rock),
+            'Y' => Some(Move::Paper),
+            'Z' => Some(Move::Scissors),
+            _ => None,
         }
     }
 
@@ -56,12 +61,12 @@ enum GameResult {
     Lose,
 }
 impl GameResult {
-    fn from_char(c: &char) -> GameResult {
+    fn from_char(c: &char) -> Option<GameResult> {
         match c {
-            'X' => GameResult::Lose,
-            'Y' => GameResult::Draw,
-            'Z' => GameResult::Win,
-            _ => panic!("Invalid input: Move not in list"),
+            'X' => Some(GameResult::Lose),
+            'Y' => Some(GameResult::Draw),
+            'Z' => Some(GameResult::Win),
+            _ => None,
         }
     }
 
@@ -74,44 +79,91 @@ impl GameResult {
     }
 }
 
-pub fn part1(input: &[String]) -> u32 {
+fn parse_line(line_number: usize, line: &str) -> Result<(char, char)> {
+    let mut chars = line.chars();
+    match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some(first), Some(' '), Some(second), None) => Ok((first, second)),
+        _ => Err(Error::at_line(
+            line_number,
+            format!("invalid strategy \"{line}\""),
+        )),
+    }
+}
+
+fn invalid_char(line_number: usize, c: char) -> Error {
+    Error::at_line(line_number, format!("invalid character '{c}'"))
+}
+
+// Returns the two characters of each round
+pub fn parse(input: &str) -> Result<Vec<(char, char)>> {
     input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_line(i + 1, line))
+        .collect()
+}
+
+pub fn part1(rounds: &[(char, char)]) -> Result<u32> {
+    rounds
         .iter()
-        .map(|line| {
-            let p1 = Move::from_char(&line.chars().next().unwrap());
-            let p2 = Move::from_char(&line.chars().nth(2).unwrap());
+        .enumerate()
+        .map(|(i, &(c1, c2))| {
+            let p1 = Move::from_char(&c1).ok_or_else(|| invalid_char(i + 1, c1))?;
+            let p2 = Move::from_char(&c2).ok_or_else(|| invalid_char(i + 1, c2))?;
 
-            p2.to_score() + p2.get_result(&p1).to_score()
+            Ok(p2.to_score() + p2.get_result(&p1).to_score())
         })
         .sum()
 }
 
-pub fn part2(input: &[String]) -> u32 {
-    input
+pub fn part2(rounds: &[(char, char)]) -> Result<u32> {
+    rounds
         .iter()
-        .map(|line| {
-            let p1 = Move::from_char(&line.chars().next().unwrap());
-            let game_result = GameResult::from_char(&line.chars().nth(2).unwrap());
+        .enumerate()
+        .map(|(i, &(c1, c2))| {
+            let p1 = Move::from_char(&c1).ok_or_else(|| invalid_char(i + 1, c1))?;
+            let game_result = GameResult::from_char(&c2).ok_or_else(|| invalid_char(i + 1, c2))?;
 
-            p1.get_opposing_move(&game_result).to_score() + game_result.to_score()
+            Ok(p1.get_opposing_move(&game_result).to_score() + game_result.to_score())
         })
         .sum()
 }
 
+pub struct Day2;
+
+impl Solution for Day2 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 2;
+
+    type Input<'a> = Vec<(char, char)>;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<(char, char)>> {
+        parse(input)
+    }
+
+    fn part1(&self, rounds: &Vec<(char, char)>) -> Result<u32> {
+        part1(rounds)
+    }
+
+    fn part2(&self, rounds: &Vec<(char, char)>) -> Result<u32> {
+        part2(rounds)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day2.txt")), 15);
-        assert_eq!(part1(&read_input_from_file("input/day2.txt")), 15523);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day2, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day2.txt")), 12);
-        assert_eq!(part2(&read_input_from_file("input/day2.txt")), 15702);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day2, Part::Two)
     }
 }
@@ -1,57 +1,99 @@
-fn mix(input: &[String], num_iterations: usize, decryption_key: i64) -> i64 {
-    let mut v: Vec<(usize, i64)> = input
-        .iter()
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::Ring,
+};
+
+pub fn parse(input: &str) -> Result<Vec<i64>> {
+    let numbers: Vec<i64> = input
+        .lines()
         .enumerate()
-        .map(|(i, line)| (i, line.parse::<i64>().unwrap() * decryption_key))
-        .collect();
+        .map(|(i, line)| {
+            line.parse::<i64>()
+                .map_err(|_| Error::at_line(i + 1, format!("invalid number \"{line}\"")))
+        })
+        .collect::<Result<_>>()?;
+
+    if numbers.len() < 2 {
+        return Err(Error::new("at least two numbers are needed for mixing"));
+    }
+    Ok(numbers)
+}
 
+fn mix(numbers: &[i64], num_iterations: usize, decryption_key: i64) -> Result<i64> {
+    let overflow = || Error::new("the numbers are too large to decrypt");
+    let numbers = numbers
+        .iter()
+        .map(|num| num.checked_mul(decryption_key).ok_or_else(overflow))
+        .collect::<Result<_>>()?;
+    let mut ring = Ring::new(numbers);
     for _ in 0..num_iterations {
-        for mix_num in 0..v.len() {
-            let old_pos = v.iter().position(|(i, _)| *i == mix_num).unwrap();
-            let elem = v.remove(old_pos);
-            let new_pos = usize::try_from(
-                (i64::try_from(old_pos).unwrap() + elem.1)
-                    .rem_euclid(i64::try_from(v.len()).unwrap()),
-            )
-            .unwrap();
-            v.insert(new_pos, elem);
+        for id in 0..ring.len() {
+            ring.move_by(id, *ring.value(id));
         }
     }
 
-    let zero_index = v.iter().position(|(_, val)| *val == 0).unwrap();
+    let zero_index = ring
+        .iter()
+        .position(|val| *val == 0)
+        .ok_or_else(|| Error::new("the file does not contain 0"))?;
 
-    let mut sum = 0;
-    sum += v[(zero_index + 1000).rem_euclid(v.len())].1;
-    sum += v[(zero_index + 3000).rem_euclid(v.len())].1;
-    sum += v[(zero_index + 2000).rem_euclid(v.len())].1;
-    sum
+    [1000, 2000, 3000]
+        .iter()
+        .map(|offset| ring.get((zero_index + offset) % ring.len()).unwrap())
+        .try_fold(0i64, |sum, num| sum.checked_add(*num))
+        .ok_or_else(overflow)
 }
 
-pub fn part1(input: &[String]) -> i64 {
-    mix(input, 1, 1)
+pub fn part1(numbers: &[i64]) -> Result<i64> {
+    mix(numbers, 1, 1)
 }
 
-pub fn part2(input: &[String]) -> i64 {
-    mix(input, 10, 811589153)
+pub fn part2(numbers: &[i64]) -> Result<i64> {
+    mix(numbers, 10, 811589153)
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 20;
+
+    type Input<'a> = Vec<i64>;
+    type Output1 = i64;
+    type Output2 = i64;
+
+    fn parse(&self, input: &str) -> Result<Vec<i64>> {
+        parse(input)
+    }
+
+    fn part1(&self, numbers: &Vec<i64>) -> Result<i64> {
+        part1(numbers)
+    }
+
+    fn part2(&self, numbers: &Vec<i64>) -> Result<i64> {
+        part2(numbers)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
+
+    #[test]
+    fn test_overflow() {
+        assert!(part2(&[0, i64::MAX / 1000]).is_err());
+        assert!(part1(&[0, i64::MAX, i64::MAX]).is_err());
+    }
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day20.txt")), 3);
-        assert_eq!(part1(&read_input_from_file("input/day20.txt")), 13883);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day20, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day20.txt")), 1623178306);
-        assert_eq!(
-            part2(&read_input_from_file("input/day20.txt")),
-            19185967576920
-        );
+    fn test_part2() -> Result<()> {
+        check_answers(&Day20, Part::Two)
     }
 }
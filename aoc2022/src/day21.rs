@@ -1,205 +1,200 @@
-use std::{collections::HashMap, str::FromStr};
+use std::collections::{HashMap, HashSet};
 
-type MonkeyId = i64;
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::expr::{self, Expr, Op},
+};
 
-fn string_to_id(s: &str) -> MonkeyId {
-    s.chars()
-        .enumerate()
-        .map(|(i, c)| (c as i64) * 1000i64.pow(u32::try_from(i).unwrap()))
-        .sum()
-}
-
-fn parse_monkeys(input: &[String]) -> HashMap<MonkeyId, Monkey> {
-    let monkey_map = input
-        .iter()
-        .map(|s| {
-            let (monkey_name, monkey_content) = s.split_once(": ").unwrap();
-            (
-                string_to_id(monkey_name),
-                (monkey_content.parse::<Monkey>().unwrap()),
-            )
-        })
-        .collect::<HashMap<MonkeyId, Monkey>>();
-    monkey_map
-}
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
 
 #[derive(Debug, Clone)]
-enum NumOp {
-    Add,
-    Sub,
-    Mul,
-    Div,
-    Eql,
+pub enum Monkey<'a> {
+    YellNum(i64),
+    YellOp(&'a str, Op, &'a str),
 }
 
-impl NumOp {
-    fn do_op(&self, a: i64, b: i64) -> i64 {
-        match self {
-            NumOp::Add => a + b,
-            NumOp::Sub => a - b,
-            NumOp::Mul => a * b,
-            NumOp::Div => a / b,
-            NumOp::Eql => panic!("Cannot perform equal operation."),
-        }
-    }
-
-    fn undo_op(&self, target: i64, num: i64, num_is_lhs: bool) -> i64 {
-        match (self, num_is_lhs) {
-            (NumOp::Add, _) => target - num,
-            (NumOp::Sub, true) => target + num,
-            (NumOp::Sub, false) => num - target,
-            (NumOp::Mul, _) => target / num,
-            (NumOp::Div, true) => target * num,
-            (NumOp::Div, false) => num / target,
-            (NumOp::Eql, _) => num,
-        }
+fn parse_monkey(s: &str) -> Option<(&str, Monkey<'_>)> {
+    let (name, job) = s.split_once(": ")?;
+    if let Ok(num) = job.parse::<i64>() {
+        return Some((name, Monkey::YellNum(num)));
     }
-}
 
-#[derive(Debug, Clone)]
-struct MonkeyOp {
-    lhs: MonkeyId,
-    rhs: MonkeyId,
-    op: NumOp,
+    let [lhs, op, rhs] = job.split(' ').collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let op = match op {
+        "+" => Op::Add,
+        "-" => Op::Sub,
+        "*" => Op::Mul,
+        "/" => Op::Div,
+        _ => return None,
+    };
+    Some((name, Monkey::YellOp(lhs, op, rhs)))
 }
 
-#[derive(Debug, Clone)]
-enum MonkeyType {
-    YellNum(i64),
-    YellOp(MonkeyOp),
+// Returns the job of each monkey, keyed by its name
+pub fn parse(input: &str) -> Result<HashMap<&str, Monkey<'_>>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, s)| {
+            parse_monkey(s).ok_or_else(|| Error::at_line(i + 1, format!("invalid monkey \"{s}\"")))
+        })
+        .collect()
 }
 
-#[derive(Debug, Clone)]
-struct Monkey {
-    monkey_type: MonkeyType,
-    is_in_human_branch: bool,
+struct ExprBuilder<'a> {
+    monkeys: &'a HashMap<&'a str, Monkey<'a>>,
+    unknown: Option<&'a str>,
+    // The monkeys whose expression is currently being built, to detect
+    // monkeys that depend on themselves
+    pending: HashSet<&'a str>,
+    // The expressions built so far, so that a monkey that several others
+    // listen to is only built once
+    built: HashMap<&'a str, Expr>,
 }
 
-impl FromStr for Monkey {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(num) = s.parse::<i64>() {
-            return Ok(Monkey {
-                monkey_type: MonkeyType::YellNum(num),
-                is_in_human_branch: false,
-            });
+impl<'a> ExprBuilder<'a> {
+    fn new(monkeys: &'a HashMap<&'a str, Monkey<'a>>, unknown: Option<&'a str>) -> Self {
+        Self {
+            monkeys,
+            unknown,
+            pending: HashSet::new(),
+            built: HashMap::new(),
         }
-        let mut it = s.split(' ');
-        Ok(Monkey {
-            monkey_type: MonkeyType::YellOp(MonkeyOp {
-                lhs: string_to_id(it.next().unwrap()),
-                op: match it.next().unwrap() {
-                    "+" => NumOp::Add,
-                    "-" => NumOp::Sub,
-                    "*" => NumOp::Mul,
-                    "/" => NumOp::Div,
-                    invalid_operator => panic!("Invalid operator \"{invalid_operator}\""),
-                },
-                rhs: string_to_id(it.next().unwrap()),
-            }),
-            is_in_human_branch: false,
-        })
     }
-}
-
-fn get_value(map: &HashMap<MonkeyId, Monkey>, m_id: MonkeyId) -> i64 {
-    match map.get(&m_id).unwrap().clone().monkey_type {
-        MonkeyType::YellNum(i) => i,
-        MonkeyType::YellOp(operation) => {
-            let lhs = get_value(map, operation.lhs);
-            let rhs = get_value(map, operation.rhs);
-
-            // Not updating the map is faster in this case
 
-            operation.op.do_op(lhs, rhs)
+    fn build(&mut self, name: &'a str) -> Result<Expr> {
+        if self.unknown == Some(name) {
+            return Ok(Expr::Unknown);
+        }
+        if let Some(expr) = self.built.get(name) {
+            return Ok(expr.clone());
         }
-    }
-}
-
-fn mark_human_branch(map: &mut HashMap<MonkeyId, Monkey>, m_id: MonkeyId) -> bool {
-    if m_id == string_to_id("humn") {
-        map.get_mut(&m_id).unwrap().is_in_human_branch = true;
-        return true;
-    }
 
-    match map.get(&m_id).unwrap().clone().monkey_type {
-        MonkeyType::YellNum(_) => false,
-        MonkeyType::YellOp(op) => {
-            let lhs = mark_human_branch(map, op.lhs);
-            let rhs = mark_human_branch(map, op.rhs);
-            if lhs || rhs {
-                map.get_mut(&m_id).unwrap().is_in_human_branch = true
+        let monkey = self
+            .monkeys
+            .get(name)
+            .ok_or_else(|| Error::new(format!("monkey {name} does not exist")))?;
+        match *monkey {
+            Monkey::YellNum(num) => Ok(Expr::num(num)),
+            Monkey::YellOp(lhs, op, rhs) => {
+                if !self.pending.insert(name) {
+                    return Err(Error::new(format!("monkey {name} depends on itself")));
+                }
+                let expr = Expr::binary(op, self.build(lhs)?, self.build(rhs)?);
+                self.pending.remove(name);
+
+                // Everything without the unknown is folded right away, and
+                // the unknown may only be used once, so that the expressions
+                // stay small
+                let expr = match expr.count_unknowns() {
+                    0 => Expr::Num(expr.eval()?),
+                    1 => expr,
+                    _ => {
+                        return Err(Error::new(format!(
+                            "{} is used more than once",
+                            self.unknown.unwrap_or_default()
+                        )))
+                    }
+                };
+                self.built.insert(name, expr.clone());
+                Ok(expr)
             }
-            lhs || rhs
         }
     }
 }
 
-fn get_human_yell(map: &HashMap<MonkeyId, Monkey>, m_id: MonkeyId, target: i64) -> i64 {
-    if m_id == string_to_id("humn") {
-        return target;
+fn to_integer(value: num::Rational64) -> Result<i64> {
+    if value.is_integer() {
+        Ok(value.to_integer())
+    } else {
+        Err(Error::new(format!("{value} is not an integer")))
     }
+}
 
-    let op: MonkeyOp = match map.get(&m_id).unwrap().clone().monkey_type {
-        MonkeyType::YellOp(op) => op,
-        MonkeyType::YellNum(_) => panic!(),
-    };
+pub fn part1(monkeys: &HashMap<&str, Monkey<'_>>) -> Result<i64> {
+    let expr = ExprBuilder::new(monkeys, None).build(ROOT)?;
+    to_integer(expr.eval()?)
+}
 
-    let (human_branch, other_branch, num_is_lhs) = {
-        if map.get(&op.lhs).unwrap().is_in_human_branch {
-            (op.lhs, op.rhs, true)
-        } else {
-            (op.rhs, op.lhs, false)
-        }
+pub fn part2(monkeys: &HashMap<&str, Monkey<'_>>) -> Result<i64> {
+    let Some(Monkey::YellOp(lhs, _, rhs)) = monkeys.get(ROOT) else {
+        return Err(Error::new("root monkey should yell an operation"));
     };
+    let mut builder = ExprBuilder::new(monkeys, Some(HUMAN));
+    let (lhs, rhs) = (builder.build(lhs)?, builder.build(rhs)?);
 
-    let other_branch_value = get_value(map, other_branch);
-    let new_target = op.op.undo_op(target, other_branch_value, num_is_lhs);
-    get_human_yell(map, human_branch, new_target)
+    match lhs.count_unknowns() + rhs.count_unknowns() {
+        0 => return Err(Error::new(format!("{ROOT} does not depend on {HUMAN}"))),
+        1 => {}
+        _ => return Err(Error::new(format!("{HUMAN} is used more than once"))),
+    }
+    let value = expr::solve(&lhs, &rhs)
+        .map_err(|err| Error::new(format!("cannot solve for {HUMAN}: {}", err.reason)))?;
+    to_integer(value)
 }
 
-pub fn part1(input: &[String]) -> i64 {
-    let monkey_map = parse_monkeys(input);
-    get_value(&monkey_map, string_to_id("root"))
-}
+pub struct Day21;
+
+impl Solution for Day21 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 21;
 
-pub fn part2(input: &[String]) -> i64 {
-    let mut monkey_map = parse_monkeys(input);
+    type Input<'a> = HashMap<&'a str, Monkey<'a>>;
+    type Output1 = i64;
+    type Output2 = i64;
 
-    match &mut monkey_map
-        .get_mut(&string_to_id("root"))
-        .unwrap()
-        .monkey_type
-    {
-        MonkeyType::YellOp(op) => op.op = NumOp::Eql,
-        _ => panic!("root node should be an operation"),
+    fn parse<'a>(&self, input: &'a str) -> Result<HashMap<&'a str, Monkey<'a>>> {
+        parse(input)
     }
 
-    mark_human_branch(&mut monkey_map, string_to_id("root"));
-    get_human_yell(&monkey_map, string_to_id("root"), 0)
+    fn part1(&self, monkeys: &HashMap<&str, Monkey<'_>>) -> Result<i64> {
+        part1(monkeys)
+    }
+
+    fn part2(&self, monkeys: &HashMap<&str, Monkey<'_>>) -> Result<i64> {
+        part2(monkeys)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day21.txt")), 152);
-        assert_eq!(
-            part1(&read_input_from_file("input/day21.txt")),
-            158661812617812
-        );
+    fn test_shared_monkeys() -> Result<()> {
+        // Every monkey listens to the one before it twice, which doubles the
+        // size of the expression tree with each monkey
+        let mut input = "m0: 1\n".to_owned();
+        for i in 1..60 {
+            input += &format!("m{i}: m{} + m{}\n", i - 1, i - 1);
+        }
+        input += "root: m59 - m58\nhumn: 5\n";
+        assert_eq!(part1(&parse(&input)?)?, 1 << 58);
+        Ok(())
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day21.txt")), 301);
+    fn test_human_used_twice() -> Result<()> {
+        let monkeys = parse("root: a + b\na: humn * c\nb: humn + c\nc: 2\nhumn: 5\n")?;
         assert_eq!(
-            part2(&read_input_from_file("input/day21.txt")),
-            3352886133831
+            part2(&monkeys).map_err(|err| err.reason),
+            Err("humn is used more than once".to_owned())
         );
+        Ok(())
+    }
+
+    #[test]
+    fn test_part1() -> Result<()> {
+        check_answers(&Day21, Part::One)
+    }
+
+    #[test]
+    fn test_part2() -> Result<()> {
+        check_answers(&Day21, Part::Two)
     }
 }
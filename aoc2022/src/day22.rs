@@ -1,9 +1,15 @@
 use itertools::Itertools;
-use std::fmt;
-
-use crate::util::Point;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
-const NUM_EDGES: usize = 14;
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::{search::Search, Grid, Point},
+    visualize::{NoObserver, Observer, Palette},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Tile {
@@ -12,27 +18,21 @@ enum Tile {
     Outside,
 }
 
-impl fmt::Display for Tile {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Tile::Open => ".",
-                Tile::Wall => "#",
-                Tile::Outside => " ",
-            }
-        )
-    }
-}
-
 impl Tile {
-    fn from_char(s: char) -> Result<Self, ()> {
+    fn from_char(s: char) -> Option<Self> {
         match s {
-            '.' => Ok(Tile::Open),
-            '#' => Ok(Tile::Wall),
-            ' ' => Ok(Tile::Outside),
-            _ => Err(()),
+            '.' => Some(Tile::Open),
+            '#' => Some(Tile::Wall),
+            ' ' => Some(Tile::Outside),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Tile::Open => '.',
+            Tile::Wall => '#',
+            Tile::Outside => ' ',
         }
     }
 }
@@ -46,6 +46,13 @@ enum Direction {
 }
 
 impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
     fn reverse(&self) -> Self {
         match self {
             Direction::Up => Direction::Down,
@@ -73,22 +80,31 @@ impl Direction {
         }
     }
 
+    fn to_char(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+
     fn step(&self, p: &Point<i32>) -> Point<i32> {
         match self {
-            Direction::Up => Point { x: p.x, y: p.y - 1 },
-            Direction::Right => Point { x: p.x + 1, y: p.y },
-            Direction::Down => Point { x: p.x, y: p.y + 1 },
-            Direction::Left => Point { x: p.x - 1, y: p.y },
+            Direction::Up => Point::new(p.x(), p.y() - 1),
+            Direction::Right => Point::new(p.x() + 1, p.y()),
+            Direction::Down => Point::new(p.x(), p.y() + 1),
+            Direction::Left => Point::new(p.x() - 1, p.y()),
         }
     }
 }
 
 fn is_on_line(p: Point<i32>, line: &(Point<i32>, Point<i32>)) -> bool {
-    let p1x = i32::min(line.0.x, line.1.x);
-    let p2x = i32::max(line.0.x, line.1.x);
-    let p1y = i32::min(line.0.y, line.1.y);
-    let p2y = i32::max(line.0.y, line.1.y);
-    p1x <= p.x && p.x <= p2x && p1y <= p.y && p.y <= p2y
+    let p1x = i32::min(line.0.x(), line.1.x());
+    let p2x = i32::max(line.0.x(), line.1.x());
+    let p1y = i32::min(line.0.y(), line.1.y());
+    let p2y = i32::max(line.0.y(), line.1.y());
+    p1x <= p.x() && p.x() <= p2x && p1y <= p.y() && p.y() <= p2y
 }
 
 fn map_onto_line(v: i32, f1: i32, f2: i32, t1: i32, t2: i32) -> i32 {
@@ -106,172 +122,274 @@ fn map_point(
 
     let (t1, t2) = if flip { (t2, t1) } else { (t1, t2) };
 
-    debug_assert!((f1.x == f2.x) != (f1.y == f2.y));
-    debug_assert!((t1.x == t2.x) != (t1.y == t2.y));
+    debug_assert!((f1.x() == f2.x()) != (f1.y() == f2.y()));
+    debug_assert!((t1.x() == t2.x()) != (t1.y() == t2.y()));
 
-    if (f1.x == f2.x) != (t1.x == t2.x) {
-        return Point {
-            x: map_onto_line(p.y, f1.y, f2.y, t1.x, t2.x),
-            y: map_onto_line(p.x, f1.x, f2.x, t1.y, t2.y),
-        };
+    if (f1.x() == f2.x()) != (t1.x() == t2.x()) {
+        return Point::new(
+            map_onto_line(p.y(), f1.y(), f2.y(), t1.x(), t2.x()),
+            map_onto_line(p.x(), f1.x(), f2.x(), t1.y(), t2.y()),
+        );
+    }
+
+    Point::new(
+        map_onto_line(p.x(), f1.x(), f2.x(), t1.x(), t2.x()),
+        map_onto_line(p.y(), f1.y(), f2.y(), t1.y(), t2.y()),
+    )
+}
+
+type Vec3 = [i32; 3];
+
+fn neg(v: Vec3) -> Vec3 {
+    v.map(|c| -c)
+}
+
+// Where a face of the net ends up on the cube: its outward normal and the
+// directions that its right and down sides point to
+#[derive(Debug, Clone, Copy)]
+struct Orientation {
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl Orientation {
+    fn towards(&self, d: Direction) -> Vec3 {
+        match d {
+            Direction::Up => neg(self.down),
+            Direction::Right => self.right,
+            Direction::Down => self.down,
+            Direction::Left => neg(self.right),
+        }
     }
 
-    Point {
-        x: map_onto_line(p.x, f1.x, f2.x, t1.x, t2.x),
-        y: map_onto_line(p.y, f1.y, f2.y, t1.y, t2.y),
+    // The orientation of the face next to this one on the side `d`, after
+    // folding it away from the viewer
+    fn fold(&self, d: Direction) -> Self {
+        let normal = self.towards(d);
+        match d {
+            Direction::Up => Self {
+                normal,
+                down: self.normal,
+                ..*self
+            },
+            Direction::Down => Self {
+                normal,
+                down: neg(self.normal),
+                ..*self
+            },
+            Direction::Left => Self {
+                normal,
+                right: self.normal,
+                ..*self
+            },
+            Direction::Right => Self {
+                normal,
+                right: neg(self.normal),
+                ..*self
+            },
+        }
     }
 }
 
+// The edges of the net that are glued together when it is folded into a
+// cube. Each edge is the line of outside tiles next to a face, and all edges
+// run clockwise around the net, so edges that are glued together run in
+// opposite directions.
 struct BoxWrap {
-    edges: [(Point<i32>, Point<i32>); NUM_EDGES],
-    map_to: [usize; NUM_EDGES],
-    edge_dirs: [Direction; NUM_EDGES],
+    edges: Vec<(Point<i32>, Point<i32>)>,
+    map_to: Vec<usize>,
+    edge_dirs: Vec<Direction>,
 }
 
-const BOX_WRAP_SAMPLE_INPUT: BoxWrap = BoxWrap {
-    edges: [
-        (Point { x: 9, y: 0 }, Point { x: 12, y: 0 }),
-        (Point { x: 13, y: 1 }, Point { x: 13, y: 4 }),
-        (Point { x: 13, y: 5 }, Point { x: 13, y: 8 }),
-        (Point { x: 13, y: 8 }, Point { x: 16, y: 8 }),
-        (Point { x: 17, y: 9 }, Point { x: 17, y: 12 }),
-        (Point { x: 16, y: 13 }, Point { x: 13, y: 13 }),
-        (Point { x: 12, y: 13 }, Point { x: 9, y: 13 }),
-        (Point { x: 8, y: 12 }, Point { x: 8, y: 9 }),
-        (Point { x: 8, y: 9 }, Point { x: 5, y: 9 }),
-        (Point { x: 4, y: 9 }, Point { x: 1, y: 9 }),
-        (Point { x: 0, y: 8 }, Point { x: 0, y: 5 }),
-        (Point { x: 1, y: 4 }, Point { x: 4, y: 4 }),
-        (Point { x: 5, y: 4 }, Point { x: 8, y: 4 }),
-        (Point { x: 8, y: 4 }, Point { x: 8, y: 1 }),
-    ],
-    map_to: [11, 4, 3, 2, 1, 10, 9, 8, 7, 6, 5, 0, 13, 12],
-    edge_dirs: [
-        Direction::Up,
-        Direction::Right,
-        Direction::Right,
-        Direction::Up,
-        Direction::Right,
-        Direction::Down,
-        Direction::Down,
-        Direction::Left,
-        Direction::Down,
-        Direction::Down,
-        Direction::Left,
-        Direction::Up,
-        Direction::Up,
-        Direction::Left,
-    ],
-};
+impl BoxWrap {
+    fn new(board: &Board) -> Result<Self> {
+        let num_tiles = board
+            .grid
+            .iter()
+            .filter(|(_, tile)| **tile != Tile::Outside)
+            .count();
+        let size = (1..)
+            .find(|size| 6 * size * size >= num_tiles)
+            .filter(|size| 6 * size * size == num_tiles)
+            .ok_or_else(|| Error::new("the board can't be folded into a cube"))?;
+
+        // Faces are given by their position in the net, in units of faces
+        let face_tiles = |face: Point<i32>| {
+            (0..size as i32).flat_map(move |y| {
+                (0..size as i32).map(move |x| {
+                    Point::new(
+                        face.x() * size as i32 + x + 1,
+                        face.y() * size as i32 + y + 1,
+                    )
+                })
+            })
+        };
+        let faces: HashSet<Point<i32>> = (0..board.get_height() as i32 / size as i32)
+            .flat_map(|y| {
+                (0..board.get_width() as i32 / size as i32).map(move |x| Point::new(x, y))
+            })
+            .filter(|face| {
+                face_tiles(*face)
+                    .all(|p| board.grid.get(p).is_some_and(|tile| *tile != Tile::Outside))
+            })
+            .collect();
+        if faces.len() != 6 {
+            return Err(Error::new("the board can't be folded into a cube"));
+        }
 
-const BOX_WRAP_REAL_INPUT: BoxWrap = BoxWrap {
-    edges: [
-        (Point { x: 51, y: 0 }, Point { x: 100, y: 0 }),
-        (Point { x: 101, y: 0 }, Point { x: 150, y: 0 }),
-        (Point { x: 151, y: 1 }, Point { x: 151, y: 50 }),
-        (Point { x: 150, y: 51 }, Point { x: 101, y: 51 }),
-        (Point { x: 101, y: 51 }, Point { x: 101, y: 100 }),
-        (Point { x: 101, y: 101 }, Point { x: 101, y: 150 }),
-        (Point { x: 100, y: 151 }, Point { x: 51, y: 151 }),
-        (Point { x: 51, y: 151 }, Point { x: 51, y: 200 }),
-        (Point { x: 50, y: 201 }, Point { x: 1, y: 201 }),
-        (Point { x: 0, y: 200 }, Point { x: 0, y: 151 }),
-        (Point { x: 0, y: 150 }, Point { x: 0, y: 101 }),
-        (Point { x: 1, y: 100 }, Point { x: 50, y: 100 }),
-        (Point { x: 50, y: 100 }, Point { x: 50, y: 51 }),
-        (Point { x: 50, y: 50 }, Point { x: 50, y: 1 }),
-    ],
-    map_to: [9, 8, 5, 4, 3, 2, 7, 6, 1, 0, 13, 12, 11, 10],
-    edge_dirs: [
-        Direction::Up,
-        Direction::Up,
-        Direction::Right,
-        Direction::Down,
-        Direction::Right,
-        Direction::Right,
-        Direction::Down,
-        Direction::Right,
-        Direction::Down,
-        Direction::Left,
-        Direction::Left,
-        Direction::Up,
-        Direction::Left,
-        Direction::Left,
-    ],
-};
+        let first = *faces
+            .iter()
+            .min_by_key(|face| (face.y(), face.x()))
+            .unwrap();
+        let mut orientations = HashMap::from([(
+            first,
+            Orientation {
+                normal: [0, 0, 1],
+                right: [1, 0, 0],
+                down: [0, 1, 0],
+            },
+        )]);
+        Search::new().flood_fill([first], |face| {
+            let orientation = orientations[face];
+            Direction::ALL
+                .into_iter()
+                .map(|d| (d, d.step(face)))
+                .filter(|(_, next)| faces.contains(next))
+                .map(|(d, next)| {
+                    orientations
+                        .entry(next)
+                        .or_insert_with(|| orientation.fold(d));
+                    next
+                })
+                .collect::<Vec<_>>()
+        });
+        if orientations.values().map(|o| o.normal).unique().count() != 6 {
+            return Err(Error::new("the board can't be folded into a cube"));
+        }
 
+        let mut edges = Vec::new();
+        let mut edge_dirs = Vec::new();
+        let mut cube_edges = Vec::new();
+        for (face, orientation) in orientations
+            .iter()
+            .sorted_by_key(|(face, _)| (face.y(), face.x()))
+        {
+            let size = size as i32;
+            let (x0, y0) = (face.x() * size + 1, face.y() * size + 1);
+            let (x1, y1) = (x0 + size - 1, y0 + size - 1);
+
+            for d in Direction::ALL {
+                if faces.contains(&d.step(face)) {
+                    continue;
+                }
+                edges.push(match d {
+                    Direction::Up => (Point::new(x0, y0 - 1), Point::new(x1, y0 - 1)),
+                    Direction::Right => (Point::new(x1 + 1, y0), Point::new(x1 + 1, y1)),
+                    Direction::Down => (Point::new(x1, y1 + 1), Point::new(x0, y1 + 1)),
+                    Direction::Left => (Point::new(x0 - 1, y1), Point::new(x0 - 1, y0)),
+                });
+                edge_dirs.push(d);
+                // An edge of the cube is where two of its faces meet
+                cube_edges.push((orientation.normal, orientation.towards(d)));
+            }
+        }
+
+        let map_to = cube_edges
+            .iter()
+            .map(|(normal, towards)| {
+                cube_edges
+                    .iter()
+                    .position(|other| *other == (*towards, *normal))
+                    .ok_or_else(|| Error::new("the board can't be folded into a cube"))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            edges,
+            map_to,
+            edge_dirs,
+        })
+    }
+}
+
+#[derive(Clone)]
 struct Board {
-    arr: Vec<Vec<Tile>>,
+    grid: Grid<Tile>,
     pos: Point<i32>,
     dir: Direction,
+    // The last direction faced on every tile that has been walked on
+    trail: HashMap<Point<i32>, Direction>,
 }
 
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.arr.iter().for_each(|v| {
-            v.iter().for_each(|e| {
-                write!(f, "{}", e).unwrap();
-            });
-            writeln!(f).unwrap();
-        });
-        Ok(())
+        write!(f, "{}", self.draw())
     }
 }
 
 impl Board {
-    fn create_board(input: &[String]) -> Self {
+    fn create_board(input: &[&str]) -> Result<Self> {
         let board_height = input.len() + 2;
-        let board_width = input.iter().map(|s| s.len()).max().unwrap() + 2;
+        let board_width = input.iter().map(|s| s.len()).max().unwrap_or(0) + 2;
 
         let mut board = Board {
-            arr: vec![vec![Tile::Outside; board_width]; board_height],
-            pos: Point { x: 0, y: 0 },
+            grid: Grid::new(board_width, board_height, Tile::Outside),
+            pos: Point::new(0, 0),
             dir: Direction::Right,
+            trail: HashMap::new(),
         };
 
-        input.iter().enumerate().for_each(|(y, s)| {
-            s.chars()
-                .enumerate()
-                .for_each(|(x, c)| board.arr[y + 1][x + 1] = Tile::from_char(c).unwrap())
-        });
+        for (y, s) in input.iter().enumerate() {
+            for (x, c) in s.chars().enumerate() {
+                let p = Point::new(x as i32 + 1, y as i32 + 1);
+                board.grid[p] = Tile::from_char(c)
+                    .ok_or_else(|| Error::at_line(y + 1, format!("invalid tile '{c}'")))?;
+            }
+        }
 
-        board.pos = Point {
-            x: board.arr[1]
+        board.pos = Point::new(
+            board
+                .grid
+                .row(1)
                 .iter()
                 .find_position(|elem| **elem == Tile::Open)
-                .unwrap()
+                .ok_or_else(|| Error::at_line(1, "there is no open tile in the first row"))?
                 .0
                 .try_into()
                 .unwrap(),
-            y: 1,
-        };
-        board
+            1,
+        );
+        Ok(board)
+    }
+
+    fn draw(&self) -> Grid<char> {
+        let mut grid = self.grid.map(|tile| tile.to_char());
+        for (p, dir) in &self.trail {
+            grid[*p] = dir.to_char();
+        }
+        grid[self.pos] = '@';
+        grid
     }
 
     fn get_height(&self) -> usize {
-        self.arr.len()
+        self.grid.height()
     }
 
     fn get_width(&self) -> usize {
-        self.arr[0].len()
+        self.grid.width()
     }
 
     fn get_tile_at(&self, p: &Point<i32>) -> Tile {
-        self.arr[p.y as usize][p.x as usize]
+        self.grid[*p]
     }
 
     fn wrap_flat(&mut self, mut p: Point<i32>, d: Direction) -> (Point<i32>, Direction) {
         p = match &self.dir {
-            Direction::Up => Point {
-                x: p.x,
-                y: self.get_height() as i32 - 1,
-            },
-            Direction::Right => Point { x: 0, y: p.y },
-            Direction::Down => Point { x: p.x, y: 0 },
-            Direction::Left => Point {
-                x: self.get_width() as i32 - 1,
-                y: p.y,
-            },
+            Direction::Up => Point::new(p.x(), self.get_height() as i32 - 1),
+            Direction::Right => Point::new(0, p.y()),
+            Direction::Down => Point::new(p.x(), 0),
+            Direction::Left => Point::new(self.get_width() as i32 - 1, p.y()),
         };
         while self.get_tile_at(&p) == Tile::Outside {
             p = self.dir.step(&p);
@@ -284,8 +402,8 @@ impl Board {
         mut p: Point<i32>,
         mut d: Direction,
         box_wrap: &BoxWrap,
-    ) -> (Point<i32>, Direction) {
-        for i in 0..NUM_EDGES {
+    ) -> Result<(Point<i32>, Direction)> {
+        for i in 0..box_wrap.edges.len() {
             if is_on_line(p, &box_wrap.edges[i]) && d == box_wrap.edge_dirs[i] {
                 p = map_point(
                     p,
@@ -295,30 +413,38 @@ impl Board {
                 );
                 d = box_wrap.edge_dirs[box_wrap.map_to[i]].reverse();
                 p = d.step(&p);
-                return (p, d);
+                return Ok((p, d));
             }
         }
-        panic!("Not in a wrapping position");
+        Err(Error::new(format!(
+            "the cube layout has no edge at ({}, {})",
+            p.x(),
+            p.y()
+        )))
     }
 
-    fn make_move(&mut self, s: &str, cube_wrap_map: Option<&BoxWrap>) {
-        if s == "R" {
-            self.dir = self.dir.turn_right();
-            return;
-        }
-        if s == "L" {
-            self.dir = self.dir.turn_left();
-            return;
-        }
+    fn make_move(&mut self, m: Move, cube_wrap_map: Option<&BoxWrap>) -> Result<()> {
+        let steps = match m {
+            Move::TurnRight => {
+                self.dir = self.dir.turn_right();
+                self.trail.insert(self.pos, self.dir);
+                return Ok(());
+            }
+            Move::TurnLeft => {
+                self.dir = self.dir.turn_left();
+                self.trail.insert(self.pos, self.dir);
+                return Ok(());
+            }
+            Move::Forward(steps) => steps,
+        };
 
-        let steps = s.parse::<i32>().unwrap();
         for _ in 0..steps {
             let mut next_pos = self.dir.step(&self.pos);
             let mut next_dir = self.dir;
 
             if self.get_tile_at(&next_pos) == Tile::Outside {
                 if let Some(box_wrap) = cube_wrap_map {
-                    (next_pos, next_dir) = self.wrap_as_cube(next_pos, next_dir, box_wrap);
+                    (next_pos, next_dir) = self.wrap_as_cube(next_pos, next_dir, box_wrap)?;
                 } else {
                     (next_pos, next_dir) = self.wrap_flat(next_pos, next_dir);
                 }
@@ -329,12 +455,14 @@ impl Board {
             }
             self.pos = next_pos;
             self.dir = next_dir;
+            self.trail.insert(self.pos, self.dir);
         }
+        Ok(())
     }
 
     fn get_result(&self) -> i32 {
-        self.pos.y * 1000
-            + self.pos.x * 4
+        self.pos.y() * 1000
+            + self.pos.x() * 4
             + match self.dir {
                 Direction::Up => 3,
                 Direction::Right => 0,
@@ -344,45 +472,126 @@ impl Board {
     }
 }
 
-fn solve(input: &[String], wrap_as_cube: bool) -> i32 {
-    let mut board = Board::create_board(&input[..(input.len() - 2)]);
+#[derive(Debug, Clone, Copy)]
+enum Move {
+    TurnLeft,
+    TurnRight,
+    Forward(i32),
+}
+
+pub struct Notes {
+    board: Board,
+    path: Vec<Move>,
+    // The line of the path in the input, for errors while following it
+    path_line: usize,
+}
+
+pub fn parse(input: &str) -> Result<Notes> {
+    let lines: Vec<&str> = input.lines().collect();
+    if lines.len() < 3 {
+        return Err(Error::new("input should contain a board and a path"));
+    }
+    let board = Board::create_board(&lines[..(lines.len() - 2)])?;
 
-    let moves = input
+    let path = lines
         .last()
         .unwrap()
         .replace('R', "_R_")
         .replace('L', "_L_")
         .split('_')
-        .map(String::from)
-        .collect_vec();
+        .map(|s| match s {
+            "R" => Ok(Move::TurnRight),
+            "L" => Ok(Move::TurnLeft),
+            _ => s
+                .parse::<i32>()
+                .map(Move::Forward)
+                .map_err(|_| Error::at_line(lines.len(), format!("invalid move \"{s}\""))),
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(Notes {
+        board,
+        path,
+        path_line: lines.len(),
+    })
+}
+
+fn solve(notes: &Notes, wrap_as_cube: bool, observer: &mut dyn Observer) -> Result<i32> {
+    let mut board = notes.board.clone();
 
     let box_wrap = if wrap_as_cube {
-        if board.get_height() >= 20 {
-            Some(&BOX_WRAP_REAL_INPUT)
-        } else {
-            Some(&BOX_WRAP_SAMPLE_INPUT)
-        }
+        Some(BoxWrap::new(&board)?)
     } else {
         None
     };
 
-    moves.iter().for_each(|m| board.make_move(m, box_wrap));
+    for m in &notes.path {
+        board
+            .make_move(*m, box_wrap.as_ref())
+            .map_err(|err| err.with_line(notes.path_line))?;
+        observer.show(|| board.draw());
+    }
 
-    board.get_result()
+    Ok(board.get_result())
 }
 
-pub fn part1(input: &[String]) -> i32 {
-    solve(input, false)
+pub fn part1(notes: &Notes, observer: &mut dyn Observer) -> Result<i32> {
+    solve(notes, false, observer)
 }
 
-pub fn part2(input: &[String]) -> i32 {
-    solve(input, true)
+pub fn part2(notes: &Notes, observer: &mut dyn Observer) -> Result<i32> {
+    solve(notes, true, observer)
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 22;
+
+    type Input<'a> = Notes;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const PALETTE: Palette = Palette {
+        background: [0, 0, 0],
+        colors: &[
+            (' ', [0, 0, 0]),
+            ('.', [40, 60, 40]),
+            ('#', [150, 150, 150]),
+            ('^', [200, 60, 60]),
+            ('>', [200, 60, 60]),
+            ('v', [200, 60, 60]),
+            ('<', [200, 60, 60]),
+            ('@', [255, 255, 0]),
+        ],
+    };
+
+    fn parse(&self, input: &str) -> Result<Notes> {
+        parse(input)
+    }
+
+    fn part1(&self, notes: &Notes) -> Result<i32> {
+        part1(notes, &mut NoObserver)
+    }
+
+    fn part2(&self, notes: &Notes) -> Result<i32> {
+        part2(notes, &mut NoObserver)
+    }
+
+    fn visualize_part1(&self, notes: &Notes, observer: &mut dyn Observer) -> Option<Result<i32>> {
+        Some(part1(notes, observer))
+    }
+
+    fn visualize_part2(&self, notes: &Notes, observer: &mut dyn Observer) -> Option<Result<i32>> {
+        Some(part2(notes, observer))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[rustfmt::skip]
     #[test]
@@ -399,70 +608,124 @@ mod tests {
         assert_eq!(map_onto_line(4, 1, 4, 8, 5), 5);
 
 
-        let f1 = Point { x: 0, y: 0 };
-        let f2 = Point { x: 0, y: 3 };
-        let t1 = Point { x: 4, y: 4 };
-        let t2 = Point { x: 4, y: 7 };
-        assert_eq!(map_point(Point { x: 0, y: 0 }, &(f1, f2), &(t1, t2), false), Point { x: 4, y: 4 });
-        assert_eq!(map_point(Point { x: 0, y: 1 }, &(f1, f2), &(t1, t2), false), Point { x: 4, y: 5 });
-        assert_eq!(map_point(Point { x: 0, y: 2 }, &(f1, f2), &(t1, t2), false), Point { x: 4, y: 6 });
-        assert_eq!(map_point(Point { x: 0, y: 3 }, &(f1, f2), &(t1, t2), false), Point { x: 4, y: 7 });
-
-        let f1 = Point { x: 0, y: 0 };
-        let f2 = Point { x: 3, y: 0 };
-        let t1 = Point { x: 4, y: 4 };
-        let t2 = Point { x: 7, y: 4 };
-        assert_eq!(map_point(Point { x: 0, y: 0 }, &(f1, f2), &(t1, t2), false), Point { x: 4, y: 4 });
-        assert_eq!(map_point(Point { x: 1, y: 0 }, &(f1, f2), &(t1, t2), false), Point { x: 5, y: 4 });
-        assert_eq!(map_point(Point { x: 2, y: 0 }, &(f1, f2), &(t1, t2), false), Point { x: 6, y: 4 });
-        assert_eq!(map_point(Point { x: 3, y: 0 }, &(f1, f2), &(t1, t2), false), Point { x: 7, y: 4 });
-
-        let f1 = Point { x: 0, y: 0 };
-        let f2 = Point { x: 0, y: 3 };
-        let t1 = Point { x: 4, y: 4 };
-        let t2 = Point { x: 4, y: 7 };
-        assert_eq!(map_point(Point { x: 0, y: 0 }, &(f1, f2), &(t1, t2), true), Point { x: 4, y: 7 });
-        assert_eq!(map_point(Point { x: 0, y: 1 }, &(f1, f2), &(t1, t2), true), Point { x: 4, y: 6 });
-        assert_eq!(map_point(Point { x: 0, y: 2 }, &(f1, f2), &(t1, t2), true), Point { x: 4, y: 5 });
-        assert_eq!(map_point(Point { x: 0, y: 3 }, &(f1, f2), &(t1, t2), true), Point { x: 4, y: 4 });
-
-        let f1 = Point { x: 0, y: 0 };
-        let f2 = Point { x: 3, y: 0 };
-        let t1 = Point { x: 4, y: 4 };
-        let t2 = Point { x: 7, y: 4 };
-        assert_eq!(map_point(Point { x: 0, y: 0 }, &(f1, f2), &(t1, t2), true), Point { x: 7, y: 4 });
-        assert_eq!(map_point(Point { x: 1, y: 0 }, &(f1, f2), &(t1, t2), true), Point { x: 6, y: 4 });
-        assert_eq!(map_point(Point { x: 2, y: 0 }, &(f1, f2), &(t1, t2), true), Point { x: 5, y: 4 });
-        assert_eq!(map_point(Point { x: 3, y: 0 }, &(f1, f2), &(t1, t2), true), Point { x: 4, y: 4 });
-
-        let f1 = Point { x: 0, y: 0 };
-        let f2 = Point { x: 0, y: 3 };
-        let t1 = Point { x: 4, y: 4 };
-        let t2 = Point { x: 7, y: 4 };
-        assert_eq!(map_point(Point { x: 0, y: 0 }, &(f1, f2), &(t1, t2), false), Point { x: 4, y: 4 });
-        assert_eq!(map_point(Point { x: 0, y: 1 }, &(f1, f2), &(t1, t2), false), Point { x: 5, y: 4 });
-        assert_eq!(map_point(Point { x: 0, y: 2 }, &(f1, f2), &(t1, t2), false), Point { x: 6, y: 4 });
-        assert_eq!(map_point(Point { x: 0, y: 3 }, &(f1, f2), &(t1, t2), false), Point { x: 7, y: 4 });
-
-        let f1 = Point { x: 0, y: 0 };
-        let f2 = Point { x: 0, y: 3 };
-        let t1 = Point { x: 4, y: 4 };
-        let t2 = Point { x: 7, y: 4 };
-        assert_eq!(map_point(Point { x: 0, y: 0 }, &(f1, f2), &(t1, t2), true), Point { x: 7, y: 4 });
-        assert_eq!(map_point(Point { x: 0, y: 1 }, &(f1, f2), &(t1, t2), true), Point { x: 6, y: 4 });
-        assert_eq!(map_point(Point { x: 0, y: 2 }, &(f1, f2), &(t1, t2), true), Point { x: 5, y: 4 });
-        assert_eq!(map_point(Point { x: 0, y: 3 }, &(f1, f2), &(t1, t2), true), Point { x: 4, y: 4 });
+        let f1 = Point::new(0, 0);
+        let f2 = Point::new(0, 3);
+        let t1 = Point::new(4, 4);
+        let t2 = Point::new(4, 7);
+        assert_eq!(map_point(Point::new(0, 0), &(f1, f2), &(t1, t2), false), Point::new(4, 4));
+        assert_eq!(map_point(Point::new(0, 1), &(f1, f2), &(t1, t2), false), Point::new(4, 5));
+        assert_eq!(map_point(Point::new(0, 2), &(f1, f2), &(t1, t2), false), Point::new(4, 6));
+        assert_eq!(map_point(Point::new(0, 3), &(f1, f2), &(t1, t2), false), Point::new(4, 7));
+
+        let f1 = Point::new(0, 0);
+        let f2 = Point::new(3, 0);
+        let t1 = Point::new(4, 4);
+        let t2 = Point::new(7, 4);
+        assert_eq!(map_point(Point::new(0, 0), &(f1, f2), &(t1, t2), false), Point::new(4, 4));
+        assert_eq!(map_point(Point::new(1, 0), &(f1, f2), &(t1, t2), false), Point::new(5, 4));
+        assert_eq!(map_point(Point::new(2, 0), &(f1, f2), &(t1, t2), false), Point::new(6, 4));
+        assert_eq!(map_point(Point::new(3, 0), &(f1, f2), &(t1, t2), false), Point::new(7, 4));
+
+        let f1 = Point::new(0, 0);
+        let f2 = Point::new(0, 3);
+        let t1 = Point::new(4, 4);
+        let t2 = Point::new(4, 7);
+        assert_eq!(map_point(Point::new(0, 0), &(f1, f2), &(t1, t2), true), Point::new(4, 7));
+        assert_eq!(map_point(Point::new(0, 1), &(f1, f2), &(t1, t2), true), Point::new(4, 6));
+        assert_eq!(map_point(Point::new(0, 2), &(f1, f2), &(t1, t2), true), Point::new(4, 5));
+        assert_eq!(map_point(Point::new(0, 3), &(f1, f2), &(t1, t2), true), Point::new(4, 4));
+
+        let f1 = Point::new(0, 0);
+        let f2 = Point::new(3, 0);
+        let t1 = Point::new(4, 4);
+        let t2 = Point::new(7, 4);
+        assert_eq!(map_point(Point::new(0, 0), &(f1, f2), &(t1, t2), true), Point::new(7, 4));
+        assert_eq!(map_point(Point::new(1, 0), &(f1, f2), &(t1, t2), true), Point::new(6, 4));
+        assert_eq!(map_point(Point::new(2, 0), &(f1, f2), &(t1, t2), true), Point::new(5, 4));
+        assert_eq!(map_point(Point::new(3, 0), &(f1, f2), &(t1, t2), true), Point::new(4, 4));
+
+        let f1 = Point::new(0, 0);
+        let f2 = Point::new(0, 3);
+        let t1 = Point::new(4, 4);
+        let t2 = Point::new(7, 4);
+        assert_eq!(map_point(Point::new(0, 0), &(f1, f2), &(t1, t2), false), Point::new(4, 4));
+        assert_eq!(map_point(Point::new(0, 1), &(f1, f2), &(t1, t2), false), Point::new(5, 4));
+        assert_eq!(map_point(Point::new(0, 2), &(f1, f2), &(t1, t2), false), Point::new(6, 4));
+        assert_eq!(map_point(Point::new(0, 3), &(f1, f2), &(t1, t2), false), Point::new(7, 4));
+
+        let f1 = Point::new(0, 0);
+        let f2 = Point::new(0, 3);
+        let t1 = Point::new(4, 4);
+        let t2 = Point::new(7, 4);
+        assert_eq!(map_point(Point::new(0, 0), &(f1, f2), &(t1, t2), true), Point::new(7, 4));
+        assert_eq!(map_point(Point::new(0, 1), &(f1, f2), &(t1, t2), true), Point::new(6, 4));
+        assert_eq!(map_point(Point::new(0, 2), &(f1, f2), &(t1, t2), true), Point::new(5, 4));
+        assert_eq!(map_point(Point::new(0, 3), &(f1, f2), &(t1, t2), true), Point::new(4, 4));
+    }
+
+    // Walking straight ahead for four faces gets back to the start on every
+    // one of the eleven cube nets
+    #[test]
+    fn test_cube_nets() -> Result<()> {
+        const SIZE: usize = 3;
+        let nets = [
+            [".   ", "....", ".   "],
+            [".   ", "....", " .  "],
+            [".   ", "....", "  . "],
+            [".   ", "....", "   ."],
+            [" .  ", "....", " .  "],
+            [" .  ", "....", "  . "],
+            ["..  ", " ...", " .  "],
+            ["..  ", " ...", "  . "],
+            ["..  ", " ...", "   ."],
+            ["..  ", " .. ", "  .."],
+            ["... ", "  ...", "     "],
+        ];
+
+        for net in nets {
+            let input = net
+                .iter()
+                .flat_map(|row| {
+                    let row: String = row.chars().flat_map(|c| [c; SIZE]).collect();
+                    vec![row; SIZE]
+                })
+                .collect_vec();
+            let input = input.iter().map(String::as_str).collect_vec();
+            let mut board = Board::create_board(&input)?;
+            let box_wrap = BoxWrap::new(&board)?;
+            assert_eq!(box_wrap.edges.len(), 14);
+
+            let tiles = board
+                .grid
+                .iter()
+                .filter(|(_, tile)| **tile == Tile::Open)
+                .map(|(p, _)| p)
+                .collect_vec();
+            for p in tiles {
+                for dir in Direction::ALL {
+                    board.pos = p;
+                    board.dir = dir;
+                    board.make_move(Move::Forward(4 * SIZE as i32), Some(&box_wrap))?;
+                    assert_eq!((board.pos, board.dir), (p, dir), "{net:?}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_not_a_cube() {
+        let board = Board::create_board(&["......"]).unwrap();
+        assert!(BoxWrap::new(&board).is_err());
     }
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day22.txt")), 6032);
-        assert_eq!(part1(&read_input_from_file("input/day22.txt")), 181128);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day22, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day22.txt")), 5031);
-        assert_eq!(part2(&read_input_from_file("input/day22.txt")), 52311);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day22, Part::Two)
     }
 }
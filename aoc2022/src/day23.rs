@@ -1,10 +1,15 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fmt, ops,
+use std::ops;
+
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::{
+        automaton::{self, Automaton, BoundingBox, Sparse},
+        Grid, Point,
+    },
+    visualize::{NoObserver, Observer, Palette},
 };
 
-use crate::util::Point;
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
     North,
@@ -17,6 +22,15 @@ enum Direction {
     NorthWest,
 }
 
+// The directions the elves consider, in order. Every round starts one
+// direction further along.
+const PROPOSALS: [Direction; 4] = [
+    Direction::North,
+    Direction::South,
+    Direction::West,
+    Direction::East,
+];
+
 impl Direction {
     fn get_adjacent(&self) -> [Direction; 3] {
         match self {
@@ -32,189 +46,140 @@ impl Direction {
     }
 }
 
-impl ops::Add<Direction> for Point<i16> {
-    type Output = Point<i16>;
+impl ops::Add<Direction> for Point<i32> {
+    type Output = Point<i32>;
 
     #[rustfmt::skip]
     fn add(self, rhs: Direction) -> Self::Output {
         match rhs {
-            Direction::North =>     Point {x: self.x,       y: self.y - 1   },
-            Direction::NorthEast => Point {x: self.x + 1,   y: self.y - 1   },
-            Direction::East =>      Point {x: self.x + 1,   y: self.y       },
-            Direction::SouthEast => Point {x: self.x + 1,   y: self.y + 1   },
-            Direction::South =>     Point {x: self.x,       y: self.y + 1   },
-            Direction::SouthWest => Point {x: self.x - 1,   y: self.y + 1   },
-            Direction::West =>      Point {x: self.x - 1,   y: self.y       },
-            Direction::NorthWest => Point {x: self.x - 1,   y: self.y - 1   },
+            Direction::North =>     Point::new(self.x(), self.y() - 1),
+            Direction::NorthEast => Point::new(self.x() + 1, self.y() - 1),
+            Direction::East =>      Point::new(self.x() + 1, self.y()),
+            Direction::SouthEast => Point::new(self.x() + 1, self.y() + 1),
+            Direction::South =>     Point::new(self.x(), self.y() + 1),
+            Direction::SouthWest => Point::new(self.x() - 1, self.y() + 1),
+            Direction::West =>      Point::new(self.x() - 1, self.y()),
+            Direction::NorthWest => Point::new(self.x() - 1, self.y() - 1),
         }
     }
 }
 
-struct Grid {
-    map: HashSet<Point<i16>>,
-    dir_queue: VecDeque<Direction>,
+// Returns the positions of the elves
+pub fn parse(input: &str) -> Result<Sparse> {
+    let grid = Grid::parse(input, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })?;
+    Ok(Sparse::from_grid(&grid))
 }
 
-impl fmt::Display for Grid {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let (min_x, max_x, min_y, max_y) = self.get_bounding_box();
-
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                if self.map.contains(&Point { x, y }) {
-                    write!(f, "#")?;
-                } else {
-                    write!(f, ".")?;
-                }
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+fn propose_move(elves: &Sparse, p: Point<i32>) -> Option<Point<i32>> {
+    if elves.live_neighbors(p) == 0 {
+        return None;
     }
+
+    (0..PROPOSALS.len())
+        .map(|i| PROPOSALS[(elves.generation() + i) % PROPOSALS.len()])
+        .find(|dir| {
+            dir.get_adjacent()
+                .iter()
+                .all(|adj_dir| !elves.contains(p + *adj_dir))
+        })
+        .map(|dir| p + dir)
 }
 
-impl Grid {
-    fn new(input: &[String]) -> Self {
-        let mut g = Self {
-            map: HashSet::new(),
-            dir_queue: VecDeque::from_iter([
-                Direction::North,
-                Direction::South,
-                Direction::West,
-                Direction::East,
-            ]),
-        };
-
-        input.iter().enumerate().for_each(|(y, line)| {
-            line.chars().enumerate().for_each(|(x, c)| {
-                if c == '#' {
-                    g.map.insert(Point {
-                        x: i16::try_from(x).unwrap(),
-                        y: i16::try_from(y).unwrap(),
-                    });
-                }
-            })
-        });
-
-        g
+fn draw(elves: &Sparse, bounds: Option<BoundingBox>) -> Grid<char> {
+    let Some(bounds) = bounds else {
+        return Grid::new(0, 0, '.');
+    };
+    let mut grid = Grid::new(bounds.width(), bounds.height(), '.');
+    for p in elves.cells() {
+        grid[*p - bounds.min] = '#';
     }
+    grid
+}
 
-    fn has_neighbor(&self, p: &Point<i16>) -> bool {
-        for neighbor in p.get_neighbors() {
-            if self.map.contains(&neighbor) {
-                return true;
-            }
-        }
-        false
-    }
+pub fn part1(elves: &Sparse, observer: &mut dyn Observer) -> Result<usize> {
+    let mut elves = elves.clone();
+    automaton::run(
+        &mut elves,
+        10,
+        |e| e.step_moves(propose_move),
+        |e, bounds| observer.show(|| draw(e, bounds)),
+    );
+
+    let bounds = elves
+        .bounding_box()
+        .ok_or_else(|| Error::new("there are no elves in the grove"))?;
+    Ok(bounds.area() - elves.len())
+}
 
-    fn can_move_to_pos(&self, p: &Point<i16>, dir: &Direction) -> bool {
-        for adj_dir in dir.get_adjacent() {
-            if self.map.contains(&(*p + adj_dir)) {
-                return false;
-            }
-        }
-        true
-    }
+pub fn part2(elves: &Sparse, observer: &mut dyn Observer) -> Result<usize> {
+    let mut elves = elves.clone();
+    Ok(automaton::run_until_stable(
+        &mut elves,
+        |e| e.step_moves(propose_move),
+        |e, bounds| observer.show(|| draw(e, bounds)),
+    ))
+}
 
-    fn get_next_pos(&self, old_p: &Point<i16>) -> Option<Point<i16>> {
-        if !self.has_neighbor(old_p) {
-            return None;
-        }
+pub struct Day23;
 
-        for dir in self.dir_queue.iter() {
-            if self.can_move_to_pos(old_p, dir) {
-                return Some(*old_p + *dir);
-            }
-        }
-        None
-    }
+impl Solution for Day23 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 23;
 
-    fn perform_iteration(&mut self) -> bool {
-        let mut new_map: HashSet<Point<i16>> = HashSet::new();
-
-        let mut map_changed = false;
-        for old_p in self.map.iter() {
-            let new_p = self.get_next_pos(old_p);
-
-            if let Some(new_p) = new_p {
-                map_changed = true;
-                if !new_map.insert(new_p) {
-                    new_map.remove(&new_p);
-                    new_map.insert(*old_p);
-                    new_map.insert(new_p * 2 - *old_p);
-                }
-            } else {
-                new_map.insert(*old_p);
-            }
-        }
-        if !map_changed {
-            return false;
-        }
+    type Input<'a> = Sparse;
+    type Output1 = usize;
+    type Output2 = usize;
 
-        self.map = new_map;
-        self.dir_queue.rotate_left(1);
+    const PALETTE: Palette = Palette {
+        background: [20, 60, 20],
+        colors: &[('.', [20, 60, 20]), ('#', [200, 60, 40])],
+    };
 
-        true
+    fn parse(&self, input: &str) -> Result<Sparse> {
+        parse(input)
     }
 
-    fn get_bounding_box(&self) -> (i16, i16, i16, i16) {
-        let mut min_x = i16::MAX;
-        let mut max_x = i16::MIN;
-        let mut min_y = i16::MAX;
-        let mut max_y = i16::MIN;
-
-        for p in self.map.iter() {
-            min_x = i16::min(min_x, p.x);
-            max_x = i16::max(max_x, p.x);
-            min_y = i16::min(min_y, p.y);
-            max_y = i16::max(max_y, p.y);
-        }
-
-        (min_x, max_x, min_y, max_y)
+    fn part1(&self, elves: &Sparse) -> Result<usize> {
+        part1(elves, &mut NoObserver)
     }
 
-    fn get_grid_size(&self) -> i16 {
-        let (min_x, max_x, min_y, max_y) = self.get_bounding_box();
-        (max_x - min_x + 1) * (max_y - min_y + 1) - self.map.len() as i16
+    fn part2(&self, elves: &Sparse) -> Result<usize> {
+        part2(elves, &mut NoObserver)
     }
-}
-
-pub fn part1(input: &[String]) -> i16 {
-    let mut grid = Grid::new(input);
 
-    for _ in 0..10 {
-        grid.perform_iteration();
+    fn visualize_part1(
+        &self,
+        elves: &Sparse,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<usize>> {
+        Some(part1(elves, observer))
     }
 
-    grid.get_grid_size()
-}
-
-pub fn part2(input: &[String]) -> i16 {
-    let mut grid = Grid::new(input);
-
-    let mut i = 1;
-    while grid.perform_iteration() {
-        i += 1;
+    fn visualize_part2(
+        &self,
+        elves: &Sparse,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<usize>> {
+        Some(part2(elves, observer))
     }
-
-    i
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day23.txt")), 110);
-        assert_eq!(part1(&read_input_from_file("input/day23.txt")), 4068);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day23, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day23.txt")), 20);
-        assert_eq!(part2(&read_input_from_file("input/day23.txt")), 968);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day23, Part::Two)
     }
 }
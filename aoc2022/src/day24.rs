@@ -1,202 +1,260 @@
-use std::collections::{HashSet, VecDeque};
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::{
+        automaton::{Dense, Neighborhood},
+        search::{DenseVisited, Search},
+        Grid, Point,
+    },
+    visualize::{NoObserver, Observer, Palette},
+};
 
-use crate::util::Point;
+// Every tile inside the walls stores the blizzards on it as a bit set
+const UP: u8 = 1;
+const RIGHT: u8 = 2;
+const DOWN: u8 = 4;
+const LEFT: u8 = 8;
 
-fn arrow_to_usize(arrow: char) -> Option<usize> {
-    match arrow {
-        '^' => Some(0),
-        '>' => Some(1),
-        'v' => Some(2),
-        '<' => Some(3),
-        _ => None,
-    }
+// Each blizzard comes from the tile behind it
+fn move_blizzards(n: &Neighborhood<u8>) -> u8 {
+    [
+        (UP, Point::new(0, 1)),
+        (RIGHT, Point::new(-1, 0)),
+        (DOWN, Point::new(0, -1)),
+        (LEFT, Point::new(1, 0)),
+    ]
+    .into_iter()
+    .filter(|(blizzard, from)| n.get(*from) & blizzard != 0)
+    .fold(0, |acc, (blizzard, _)| acc | blizzard)
 }
 
-struct Grid {
-    height: usize,
-    width: usize,
-    blizzards: [VecDeque<VecDeque<bool>>; 4],
-    walls: Vec<Vec<bool>>,
+pub struct Valley {
+    grid: Grid<char>,
+    // The blizzards inside the walls at every minute, until they repeat
+    blizzards: Vec<Grid<u8>>,
+    start: Point<i32>,
+    end: Point<i32>,
 }
 
-impl Grid {
-    fn new(input: &[String]) -> Self {
-        let grid_height = input.len() + 2;
-        let grid_width = input[0].len() + 2;
-
-        let blizzard = VecDeque::from(vec![
-            VecDeque::from(vec![false; grid_width - 4]);
-            grid_height - 4
-        ]);
-
-        let mut g = Grid {
-            height: grid_height,
-            width: grid_width,
-            walls: vec![vec![true; grid_width]; grid_height],
-            blizzards: [
-                blizzard.clone(),
-                blizzard.clone(),
-                blizzard.clone(),
-                blizzard.clone(),
-            ],
-        };
-
-        input.iter().enumerate().for_each(|(y, line)| {
-            line.chars().enumerate().for_each(|(x, c)| {
-                if let Some(blizzard_id) = arrow_to_usize(c) {
-                    g.blizzards[blizzard_id][y - 1][x - 1] = true;
-                }
-                if c != '#' {
-                    g.walls[y + 1][x + 1] = false;
-                }
-            })
-        });
-
-        g
-    }
-
-    fn get_cell_as_char(&self, x: usize, y: usize) -> char {
-        if self.walls[y][x] {
-            return '#';
+impl Valley {
+    fn new(input: &str) -> Result<Self> {
+        let grid = Grid::parse(input, |c| "#.^>v<".contains(c).then_some(c))?;
+        if grid.width() < 3 || grid.height() < 3 {
+            return Err(Error::new(
+                "the valley must be at least three tiles wide and tall",
+            ));
         }
 
-        if !self.in_blizzard_range(Point { x, y }) {
-            return '.';
+        for (p, c) in grid.iter() {
+            let is_border = p.x() == 0
+                || p.y() == 0
+                || p.x() as usize == grid.width() - 1
+                || p.y() as usize == grid.height() - 1;
+            if is_border && *c != '#' && *c != '.' {
+                return Err(Error::at_line(
+                    p.y() as usize + 1,
+                    "blizzard in the valley wall",
+                ));
+            }
         }
 
-        let num_blizzards = self.get_num_blizzards(Point { x, y });
-        if num_blizzards > 1 {
-            return num_blizzards.to_string().chars().last().unwrap();
-        }
+        let find_opening = |y: usize| {
+            grid.row(y)
+                .iter()
+                .position(|c| *c == '.')
+                .map(|x| Point::new(x as i32, y as i32))
+                .ok_or_else(|| Error::at_line(y + 1, "there is no opening in the valley wall"))
+        };
 
-        if self.blizzards[0][y - 2][x - 2] {
-            return '^';
-        }
-        if self.blizzards[1][y - 2][x - 2] {
-            return '>';
-        }
-        if self.blizzards[2][y - 2][x - 2] {
-            return 'v';
+        let width = grid.width() - 2;
+        let height = grid.height() - 2;
+        let mut inside = Grid::new(width, height, 0);
+        for p in inside.points() {
+            inside[p] = match grid[p + Point::new(1, 1)] {
+                '^' => UP,
+                '>' => RIGHT,
+                'v' => DOWN,
+                '<' => LEFT,
+                _ => 0,
+            };
         }
-        if self.blizzards[3][y - 2][x - 2] {
-            return '<';
+
+        // The blizzards are back where they started after a multiple of both
+        // the width and the height
+        let mut automaton = Dense::new(inside, 0).wrapping();
+        let mut blizzards = vec![automaton.grid().clone()];
+        for _ in 1..num::integer::lcm(width, height) {
+            automaton.step(move_blizzards);
+            blizzards.push(automaton.grid().clone());
         }
-        '.'
+
+        Ok(Self {
+            start: find_opening(0)?,
+            end: find_opening(grid.height() - 1)?,
+            grid,
+            blizzards,
+        })
     }
 
-    #[allow(dead_code)]
-    fn print(&self, p: Point<usize>) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if p.x == x && p.y == y {
-                    print!("E");
-                } else {
-                    print!("{}", self.get_cell_as_char(x, y));
-                }
-            }
-            println!();
+    fn is_free(&self, p: Point<i32>, time: usize) -> bool {
+        match self.grid.get(p) {
+            None | Some('#') => return false,
+            _ => {}
+        }
+        if p.y() == 0 || p.y() as usize == self.grid.height() - 1 {
+            return true;
         }
-    }
 
-    fn get_start(&self) -> Point<usize> {
-        Point { x: 2, y: 1 }
+        let blizzards = &self.blizzards[time % self.blizzards.len()];
+        blizzards[p - Point::new(1, 1)] == 0
     }
 
-    fn get_end(&self) -> Point<usize> {
-        Point {
-            x: self.width - 3,
-            y: self.height - 2,
+    fn draw(&self, time: usize, expedition: Point<i32>) -> Grid<char> {
+        let blizzards = &self.blizzards[time % self.blizzards.len()];
+        let mut grid = self.grid.map(|c| if *c == '#' { '#' } else { '.' });
+        for (p, tile) in blizzards.iter() {
+            grid[p + Point::new(1, 1)] = match *tile {
+                0 => '.',
+                UP => '^',
+                RIGHT => '>',
+                DOWN => 'v',
+                LEFT => '<',
+                _ => char::from_digit(tile.count_ones(), 10).unwrap(),
+            };
         }
+        grid[expedition] = 'E';
+        grid
     }
 
-    fn in_blizzard_range(&self, p: Point<usize>) -> bool {
-        p.is_within(2, 2, self.width - 2, self.height - 2)
-    }
+    // Returns the time when `target` is reached
+    fn search(
+        &self,
+        source: Point<i32>,
+        target: Point<i32>,
+        start_time: usize,
+        observer: &mut dyn Observer,
+    ) -> Result<usize> {
+        // The blizzards repeat, so being on a tile at the same point of their
+        // cycle as before can't lead anywhere new
+        let period = self.blizzards.len();
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let index = move |&(p, time): &(Point<i32>, usize)| {
+            (time % period * height + p.y() as usize) * width + p.x() as usize
+        };
+        let mut search = Search::with_visited(DenseVisited::new(period * width * height, index));
+        if observer.is_active() {
+            search = search.track_paths();
+        }
 
-    fn get_num_blizzards(&self, p: Point<usize>) -> usize {
-        if !self.in_blizzard_range(p) {
-            return 0;
+        let (goal, _) = search
+            .bfs(
+                (source, start_time),
+                |&(p, time)| {
+                    p.get_manhattan_neighbors()
+                        .chain([p])
+                        .filter(move |n| self.is_free(*n, time + 1))
+                        .map(move |n| (n, time + 1))
+                },
+                |(p, _)| *p == target,
+            )
+            .ok_or_else(|| Error::new("no path found"))?;
+
+        // Replays the path that was found
+        for (p, time) in search.path_to(&goal).unwrap_or_default() {
+            observer.show(|| self.draw(time, p));
         }
-        return [
-            self.blizzards[0][p.y - 2][p.x - 2],
-            self.blizzards[1][p.y - 2][p.x - 2],
-            self.blizzards[2][p.y - 2][p.x - 2],
-            self.blizzards[3][p.y - 2][p.x - 2],
-        ]
-        .iter()
-        .filter(|a| **a)
-        .count();
+        Ok(goal.1)
     }
+}
 
-    fn iterate(&mut self) {
-        self.blizzards[0].rotate_left(1);
-        self.blizzards[1].iter_mut().for_each(|a| a.rotate_right(1));
-        self.blizzards[2].rotate_right(1);
-        self.blizzards[3].iter_mut().for_each(|a| a.rotate_left(1));
-    }
+pub fn parse(input: &str) -> Result<Valley> {
+    Valley::new(input)
+}
 
-    fn search(&mut self, source: Point<usize>, target: Point<usize>) -> usize {
-        let mut time = 0usize;
+pub fn part1(valley: &Valley, observer: &mut dyn Observer) -> Result<usize> {
+    valley.search(valley.start, valley.end, 0, observer)
+}
 
-        let mut q: VecDeque<(Point<usize>, usize)> = VecDeque::new();
-        let mut visited: HashSet<(Point<usize>, usize)> = HashSet::new();
+pub fn part2(valley: &Valley, observer: &mut dyn Observer) -> Result<usize> {
+    let there = valley.search(valley.start, valley.end, 0, observer)?;
+    let back = valley.search(valley.end, valley.start, there, observer)?;
+    valley.search(valley.start, valley.end, back, observer)
+}
 
-        q.push_back((source, time));
-        visited.insert((source, time));
+pub struct Day24;
 
-        while let Some((v, v_time)) = q.pop_front() {
-            if time == v_time {
-                self.iterate();
-                time += 1;
-            }
+impl Solution for Day24 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 24;
 
-            let mut neighbors = Vec::from(v.get_manhattan_neighbors());
-            neighbors.push(v);
-
-            for n in neighbors {
-                if !self.walls[n.y][n.x] && self.get_num_blizzards(n) == 0 {
-                    if n == target {
-                        return time;
-                    }
-                    if visited.insert((n, time)) {
-                        q.push_back((n, time));
-                    }
-                }
-            }
-        }
-        panic!("No path found!")
+    type Input<'a> = Valley;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const PALETTE: Palette = Palette {
+        background: [20, 20, 40],
+        colors: &[
+            ('.', [20, 20, 40]),
+            ('#', [110, 110, 110]),
+            ('^', [150, 200, 255]),
+            ('>', [150, 200, 255]),
+            ('v', [150, 200, 255]),
+            ('<', [150, 200, 255]),
+            ('2', [110, 160, 230]),
+            ('3', [70, 120, 210]),
+            ('4', [40, 80, 190]),
+            ('E', [255, 220, 0]),
+        ],
+    };
+
+    fn parse(&self, input: &str) -> Result<Valley> {
+        parse(input)
     }
-}
 
-pub fn part1(input: &[String]) -> usize {
-    let mut g = Grid::new(input);
-    g.search(g.get_start(), g.get_end())
-}
+    fn part1(&self, valley: &Valley) -> Result<usize> {
+        part1(valley, &mut NoObserver)
+    }
 
-pub fn part2(input: &[String]) -> usize {
-    let mut g = Grid::new(input);
+    fn part2(&self, valley: &Valley) -> Result<usize> {
+        part2(valley, &mut NoObserver)
+    }
+
+    fn visualize_part1(
+        &self,
+        valley: &Valley,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<usize>> {
+        Some(part1(valley, observer))
+    }
 
-    let mut total_time = 0usize;
-    total_time += g.search(g.get_start(), g.get_end());
-    total_time += g.search(g.get_end(), g.get_start());
-    total_time += g.search(g.get_start(), g.get_end());
-    total_time
+    fn visualize_part2(
+        &self,
+        valley: &Valley,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<usize>> {
+        Some(part2(valley, observer))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
+
+    #[test]
+    fn test_blocked_valley() -> Result<()> {
+        let valley = parse("#.####\n#.#..#\n####.#")?;
+        assert!(part1(&valley, &mut NoObserver).is_err());
+        Ok(())
+    }
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day24.txt")), 18);
-        assert_eq!(part1(&read_input_from_file("input/day24.txt")), 292);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day24, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day24.txt")), 54);
-        assert_eq!(part2(&read_input_from_file("input/day24.txt")), 816);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day24, Part::Two)
     }
 }
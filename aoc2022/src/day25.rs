@@ -1,4 +1,15 @@
-fn snafu_to_dec(input: &str) -> i64 {
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+struct ParseSnafuError;
+
+fn snafu_to_dec(input: &str) -> std::result::Result<i64, ParseSnafuError> {
+    if input.is_empty() || input.len() > 27 {
+        return Err(ParseSnafuError);
+    }
     let len = input.len() - 1;
     input
         .chars()
@@ -11,73 +22,111 @@ fn snafu_to_dec(input: &str) -> i64 {
                 '0' => 0,
                 '1' => 1,
                 '2' => 2,
-                _ => panic!("Invalid SNAFU number: {c}"),
+                _ => return Err(ParseSnafuError),
             };
-            position_value * position
+            Ok(position_value * position)
         })
         .sum()
 }
 
+// Only for non-negative numbers
 fn dec_to_snafu(input: i64) -> String {
-    let number_len = 20;
-    let mut v = vec![0; number_len];
-
-    let mut remainder = input;
-    for i in (0..number_len).rev() {
-        let position_value = 5i64.pow(u32::try_from(i).unwrap());
-        v[i] = remainder / position_value;
-        remainder %= position_value;
+    if input == 0 {
+        return "0".to_owned();
     }
 
-    let mut carry = 0;
-    for i in 0..number_len {
-        let val = v[i] + carry;
-        v[i] = ((val + 2) % 5) - 2;
-        carry = (val + 2) / 5;
+    // The digits from the lowest, where a digit of 3 or 4 becomes -2 or -1
+    // and carries one into the next digit
+    let mut digits = Vec::new();
+    let mut remainder = input;
+    while remainder != 0 {
+        let digit = remainder % 5;
+        remainder /= 5;
+        digits.push(match digit {
+            3 => {
+                remainder += 1;
+                '='
+            }
+            4 => {
+                remainder += 1;
+                '-'
+            }
+            _ => char::from_digit(digit as u32, 10).unwrap(),
+        });
     }
+    digits.iter().rev().collect()
+}
 
-    v.iter()
-        .rev()
-        .skip_while(|v| **v == 0)
-        .map(|v| match v {
-            -2 => '=',
-            -1 => '-',
-            0 => '0',
-            1 => '1',
-            2 => '2',
-            _ => panic!("Invalid decimal integer for SNAFU conversion!"),
+// Returns the fuel requirements in decimal
+pub fn parse(input: &str) -> Result<Vec<i64>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, s)| {
+            snafu_to_dec(s)
+                .map_err(|_| Error::at_line(i + 1, format!("invalid SNAFU number \"{s}\"")))
         })
         .collect()
 }
 
-pub fn part1(input: &[String]) -> String {
-    dec_to_snafu(input.iter().map(|s| snafu_to_dec(s)).sum())
+pub fn part1(requirements: &[i64]) -> Result<String> {
+    let sum = requirements
+        .iter()
+        .try_fold(0i64, |sum, requirement| sum.checked_add(*requirement))
+        .ok_or_else(|| Error::new("the sum of the fuel requirements is too large"))?;
+    if sum < 0 {
+        return Err(Error::new("the sum of the fuel requirements is negative"));
+    }
+    Ok(dec_to_snafu(sum))
 }
 
-pub fn part2(_input: &[String]) -> String {
-    "".to_owned()
+pub fn part2(_requirements: &[i64]) -> Result<String> {
+    Ok("".to_owned())
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 25;
+
+    type Input<'a> = Vec<i64>;
+    type Output1 = String;
+    type Output2 = String;
+
+    fn parse(&self, input: &str) -> Result<Vec<i64>> {
+        parse(input)
+    }
+
+    fn part1(&self, requirements: &Vec<i64>) -> Result<String> {
+        part1(requirements)
+    }
+
+    fn part2(&self, requirements: &Vec<i64>) -> Result<String> {
+        part2(requirements)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
     fn test_snafu_to_dec() {
-        assert_eq!(snafu_to_dec("1=-0-2"), 1747);
-        assert_eq!(snafu_to_dec("12111"), 906);
-        assert_eq!(snafu_to_dec("2=0="), 198);
-        assert_eq!(snafu_to_dec("21"), 11);
-        assert_eq!(snafu_to_dec("2=01"), 201);
-        assert_eq!(snafu_to_dec("111"), 31);
-        assert_eq!(snafu_to_dec("20012"), 1257);
-        assert_eq!(snafu_to_dec("112"), 32);
-        assert_eq!(snafu_to_dec("1=-1="), 353);
-        assert_eq!(snafu_to_dec("1-12"), 107);
-        assert_eq!(snafu_to_dec("12"), 7);
-        assert_eq!(snafu_to_dec("1="), 3);
-        assert_eq!(snafu_to_dec("122"), 37);
+        assert_eq!(snafu_to_dec("1=-0-2"), Ok(1747));
+        assert_eq!(snafu_to_dec("12111"), Ok(906));
+        assert_eq!(snafu_to_dec("2=0="), Ok(198));
+        assert_eq!(snafu_to_dec("21"), Ok(11));
+        assert_eq!(snafu_to_dec("2=01"), Ok(201));
+        assert_eq!(snafu_to_dec("111"), Ok(31));
+        assert_eq!(snafu_to_dec("20012"), Ok(1257));
+        assert_eq!(snafu_to_dec("112"), Ok(32));
+        assert_eq!(snafu_to_dec("1=-1="), Ok(353));
+        assert_eq!(snafu_to_dec("1-12"), Ok(107));
+        assert_eq!(snafu_to_dec("12"), Ok(7));
+        assert_eq!(snafu_to_dec("1="), Ok(3));
+        assert_eq!(snafu_to_dec("122"), Ok(37));
     }
 
     #[test]
@@ -95,14 +144,19 @@ mod tests {
         assert_eq!(dec_to_snafu(7), "12");
         assert_eq!(dec_to_snafu(3), "1=");
         assert_eq!(dec_to_snafu(37), "122");
+        assert_eq!(dec_to_snafu(0), "0");
+        assert_eq!(dec_to_snafu(5i64.pow(20)), "100000000000000000000");
+        let max = "2".repeat(27);
+        assert_eq!(dec_to_snafu(snafu_to_dec(&max).unwrap()), max);
+    }
+
+    #[test]
+    fn test_sum_overflow() {
+        assert!(part1(&[i64::MAX, 1]).is_err());
     }
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day25.txt")), "2=-1=0");
-        assert_eq!(
-            part1(&read_input_from_file("input/day25.txt")),
-            "2-1-110-=01-1-0-0==2"
-        );
+    fn test_part1() -> Result<()> {
+        check_answers(&Day25, Part::One)
     }
 }
@@ -1,5 +1,10 @@
 use std::collections::HashSet;
 
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
 fn char_to_score(c: &char) -> u32 {
     let tmp = *c as u32;
     if tmp >= 97 {
@@ -8,20 +13,44 @@ fn char_to_score(c: &char) -> u32 {
     tmp - 65 + 27
 }
 
-pub fn part1(input: &[String]) -> u32 {
+fn check_rucksack(line_number: usize, line: &str) -> Result<()> {
+    match line.chars().find(|c| !c.is_ascii_alphabetic()) {
+        Some(c) => Err(Error::at_line(line_number, format!("invalid item '{c}'"))),
+        None => Ok(()),
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<&str>> {
     input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| check_rucksack(i + 1, line).map(|_| line))
+        .collect()
+}
+
+pub fn part1(rucksacks: &[&str]) -> Result<u32> {
+    rucksacks
         .iter()
-        .map(|line| {
+        .enumerate()
+        .map(|(i, line)| {
+            if line.len() % 2 != 0 {
+                return Err(Error::at_line(i + 1, "rucksack has an odd number of items"));
+            }
+
             let h1: HashSet<char> = line[..(line.len() / 2)].chars().collect();
             let h2: HashSet<char> = line[(line.len() / 2)..].chars().collect();
 
-            h1.intersection(&h2).map(char_to_score).sum::<u32>()
+            Ok(h1.intersection(&h2).map(char_to_score).sum::<u32>())
         })
         .sum()
 }
 
-pub fn part2(input: &[String]) -> u32 {
-    input
+pub fn part2(rucksacks: &[&str]) -> Result<u32> {
+    if !rucksacks.len().is_multiple_of(3) {
+        return Err(Error::new("number of rucksacks is not divisible by 3"));
+    }
+
+    Ok(rucksacks
         .chunks(3)
         .map(|lines| {
             let h1: HashSet<char> = lines[0].chars().collect();
@@ -34,23 +63,44 @@ pub fn part2(input: &[String]) -> u32 {
                 .map(char_to_score)
                 .sum::<u32>()
         })
-        .sum()
+        .sum())
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 3;
+
+    type Input<'a> = Vec<&'a str>;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Vec<&'a str>> {
+        parse(input)
+    }
+
+    fn part1(&self, rucksacks: &Vec<&str>) -> Result<u32> {
+        part1(rucksacks)
+    }
+
+    fn part2(&self, rucksacks: &Vec<&str>) -> Result<u32> {
+        part2(rucksacks)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day3.txt")), 157);
-        assert_eq!(part1(&read_input_from_file("input/day3.txt")), 8176);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day3, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day3.txt")), 70);
-        assert_eq!(part2(&read_input_from_file("input/day3.txt")), 2689);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day3, Part::Two)
     }
 }
@@ -1,15 +1,30 @@
-struct ElfPair {
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
+pub struct ElfPair {
     first: (u32, u32),
     second: (u32, u32),
 }
 
 impl ElfPair {
-    fn from_string(input: &str) -> ElfPair {
-        let mut values = input.split(&['-', ',']).map(|v| v.parse::<u32>().unwrap());
-        ElfPair {
-            first: (values.next().unwrap(), values.next().unwrap()),
-            second: (values.next().unwrap(), values.next().unwrap()),
-        }
+    fn from_string(line_number: usize, input: &str) -> Result<ElfPair> {
+        let values = input
+            .split(&['-', ','])
+            .map(|v| v.parse::<u32>().ok())
+            .collect::<Option<Vec<u32>>>()
+            .filter(|values| values.len() == 4)
+            .ok_or_else(|| {
+                Error::at_line(
+                    line_number,
+                    format!("invalid section assignment \"{input}\""),
+                )
+            })?;
+        Ok(ElfPair {
+            first: (values[0], values[1]),
+            second: (values[2], values[3]),
+        })
     }
 
     fn fully_contains(&self) -> bool {
@@ -22,36 +37,57 @@ impl ElfPair {
     }
 }
 
-pub fn part1(input: &[String]) -> usize {
+pub fn parse(input: &str) -> Result<Vec<ElfPair>> {
     input
-        .iter()
-        .map(|l| ElfPair::from_string(l))
-        .filter(|p| p.fully_contains())
-        .count()
+        .lines()
+        .enumerate()
+        .map(|(i, l)| ElfPair::from_string(i + 1, l))
+        .collect()
 }
 
-pub fn part2(input: &[String]) -> usize {
-    input
-        .iter()
-        .map(|l| ElfPair::from_string(l))
-        .filter(|p| p.partly_contains())
-        .count()
+pub fn part1(pairs: &[ElfPair]) -> Result<usize> {
+    Ok(pairs.iter().filter(|p| p.fully_contains()).count())
+}
+
+pub fn part2(pairs: &[ElfPair]) -> Result<usize> {
+    Ok(pairs.iter().filter(|p| p.partly_contains()).count())
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 4;
+
+    type Input<'a> = Vec<ElfPair>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<ElfPair>> {
+        parse(input)
+    }
+
+    fn part1(&self, pairs: &Vec<ElfPair>) -> Result<usize> {
+        part1(pairs)
+    }
+
+    fn part2(&self, pairs: &Vec<ElfPair>) -> Result<usize> {
+        part2(pairs)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day4.txt")), 2);
-        assert_eq!(part1(&read_input_from_file("input/day4.txt")), 538);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day4, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day4.txt")), 4);
-        assert_eq!(part2(&read_input_from_file("input/day4.txt")), 792);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day4, Part::Two)
     }
 }
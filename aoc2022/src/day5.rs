@@ -1,90 +1,154 @@
 use std::collections::VecDeque;
 
-pub fn part1(input: &[String]) -> String {
-    let num_columns = (input[0].len() + 1) / 4;
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
+struct Move {
+    // Where the move is in the input, for errors while rearranging
+    line_number: usize,
+    amount: usize,
+    from: usize,
+    to: usize,
+}
+
+pub struct Procedure {
+    stacks: Vec<VecDeque<char>>,
+    moves: Vec<Move>,
+}
+
+// Parses the drawing of the stacks. Also returns the index of the first line
+// after the drawing.
+fn parse_stacks(input: &[&str]) -> Result<(Vec<VecDeque<char>>, usize)> {
+    let first_line = input.first().ok_or_else(|| Error::new("input is empty"))?;
+    let num_columns = (first_line.len() + 1) / 4;
     let mut stacks: Vec<VecDeque<char>> = vec![VecDeque::new(); num_columns];
 
     let mut index: usize = 0;
     for line in input {
         index += 1;
-        if line.chars().nth(1).unwrap() == '1' {
-            break;
+        match line.chars().nth(1) {
+            Some('1') => break,
+            Some(_) => {}
+            None => return Err(Error::at_line(index, "expected a row of crates")),
         }
-        line[1..].chars().step_by(4).enumerate().for_each(|(i, c)| {
+        for (i, c) in line[1..].chars().step_by(4).enumerate() {
             if c != ' ' {
-                stacks[i].push_front(c);
+                stacks
+                    .get_mut(i)
+                    .ok_or_else(|| Error::at_line(index, "row is wider than the first row"))?
+                    .push_front(c);
             }
-        })
+        }
     }
     index += 1;
 
-    for line in &input[index..] {
-        let mut split_line = line.split(' ');
+    Ok((stacks, index))
+}
 
-        let amount = split_line.nth(1).unwrap().parse::<usize>().unwrap();
-        let from = split_line.nth(1).unwrap().parse::<usize>().unwrap() - 1;
-        let to = split_line.nth(1).unwrap().parse::<usize>().unwrap() - 1;
+fn parse_move(line_number: usize, line: &str, num_stacks: usize) -> Result<Move> {
+    let invalid_move = || Error::at_line(line_number, format!("invalid move \"{line}\""));
+    let parse_stack = |s: &str| {
+        s.parse::<usize>()
+            .ok()
+            .filter(|i| (1..=num_stacks).contains(i))
+            .map(|i| i - 1)
+            .ok_or_else(invalid_move)
+    };
 
-        for _ in 0..amount {
-            let c = stacks[from].pop_back().unwrap();
-            stacks[to].push_back(c);
-        }
+    match line.split(' ').collect::<Vec<&str>>()[..] {
+        ["move", amount, "from", from, "to", to] => Ok(Move {
+            line_number,
+            amount: amount.parse().map_err(|_| invalid_move())?,
+            from: parse_stack(from)?,
+            to: parse_stack(to)?,
+        }),
+        _ => Err(invalid_move()),
     }
-    stacks.iter().map(|s| s.back().unwrap()).collect()
 }
 
-pub fn part2(input: &[String]) -> String {
-    let num_columns = (input[0].len() + 1) / 4;
-    let mut stacks: Vec<VecDeque<char>> = vec![VecDeque::new(); num_columns];
+pub fn parse(input: &str) -> Result<Procedure> {
+    let lines: Vec<&str> = input.lines().collect();
+    let (stacks, first_move_index) = parse_stacks(&lines)?;
+    let moves = lines
+        .iter()
+        .enumerate()
+        .skip(first_move_index)
+        .map(|(i, line)| parse_move(i + 1, line, stacks.len()))
+        .collect::<Result<_>>()?;
+    Ok(Procedure { stacks, moves })
+}
 
-    let mut index: usize = 0;
-    for line in input {
-        index += 1;
-        if line.chars().nth(1).unwrap() == '1' {
-            break;
+fn rearrange(procedure: &Procedure, keep_order: bool) -> Result<String> {
+    let mut stacks = procedure.stacks.clone();
+
+    for m in &procedure.moves {
+        if stacks[m.from].len() < m.amount {
+            return Err(Error::at_line(
+                m.line_number,
+                format!("stack {} has fewer than {} crates", m.from + 1, m.amount),
+            ));
         }
-        line[1..].chars().step_by(4).enumerate().for_each(|(i, c)| {
-            if c != ' ' {
-                stacks[i].push_front(c);
-            }
-        })
+
+        let split_index = stacks[m.from].len() - m.amount;
+        let mut moved_crates: Vec<char> = stacks[m.from].drain(split_index..).collect();
+        if !keep_order {
+            moved_crates.reverse();
+        }
+        stacks[m.to].extend(moved_crates);
     }
-    index += 1;
 
-    for line in &input[index..] {
-        let mut split_line = line.split(' ');
+    stacks
+        .iter()
+        .map(|s| s.back())
+        .collect::<Option<String>>()
+        .ok_or_else(|| Error::new("a stack is empty after the rearrangement"))
+}
 
-        let amount = split_line.nth(1).unwrap().parse::<usize>().unwrap();
-        let from = split_line.nth(1).unwrap().parse::<usize>().unwrap() - 1;
-        let to = split_line.nth(1).unwrap().parse::<usize>().unwrap() - 1;
+pub fn part1(procedure: &Procedure) -> Result<String> {
+    rearrange(procedure, false)
+}
 
-        let mut tmp: Vec<char> = Vec::new();
+pub fn part2(procedure: &Procedure) -> Result<String> {
+    rearrange(procedure, true)
+}
 
-        for _ in 0..amount {
-            tmp.push(stacks[from].pop_back().unwrap());
-        }
+pub struct Day5;
 
-        for _ in 0..amount {
-            stacks[to].push_back(tmp.pop().unwrap());
-        }
+impl Solution for Day5 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 5;
+
+    type Input<'a> = Procedure;
+    type Output1 = String;
+    type Output2 = String;
+
+    fn parse(&self, input: &str) -> Result<Procedure> {
+        parse(input)
+    }
+
+    fn part1(&self, procedure: &Procedure) -> Result<String> {
+        part1(procedure)
+    }
+
+    fn part2(&self, procedure: &Procedure) -> Result<String> {
+        part2(procedure)
     }
-    stacks.iter().map(|s| s.back().unwrap()).collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day5.txt")), "CMZ");
-        assert_eq!(part1(&read_input_from_file("input/day5.txt")), "RTGWZTHLD");
+    fn test_part1() -> Result<()> {
+        check_answers(&Day5, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day5.txt")), "MCD");
-        assert_eq!(part2(&read_input_from_file("input/day5.txt")), "STHGRZZFR");
+    fn test_part2() -> Result<()> {
+        check_answers(&Day5, Part::Two)
     }
 }
@@ -1,5 +1,13 @@
-fn get_index_of_first_substring_with_unique_letters(input: &str, substring_len: usize) -> usize {
-    input
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
+fn get_index_of_first_substring_with_unique_letters(
+    input: &str,
+    substring_len: usize,
+) -> Result<usize> {
+    Ok(input
         .chars()
         .collect::<Vec<char>>()
         .windows(substring_len)
@@ -10,41 +18,58 @@ fn get_index_of_first_substring_with_unique_letters(input: &str, substring_len:
             w.dedup();
             w.len() == substring_len
         })
-        .unwrap()
+        .ok_or_else(|| Error::new("no marker found"))?
         .0
-        + substring_len // since we index from the last char
+        + substring_len) // since we index from the last char
+}
+
+pub fn parse(input: &str) -> Result<&str> {
+    Ok(input.trim_end())
 }
 
-pub fn part1(input: &[String]) -> usize {
-    get_index_of_first_substring_with_unique_letters(&input.join("\n"), 4)
+pub fn part1(signal: &str) -> Result<usize> {
+    get_index_of_first_substring_with_unique_letters(signal, 4)
 }
 
-pub fn part2(input: &[String]) -> usize {
-    get_index_of_first_substring_with_unique_letters(&input.join("\n"), 14)
+pub fn part2(signal: &str) -> Result<usize> {
+    get_index_of_first_substring_with_unique_letters(signal, 14)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 6;
+
+    type Input<'a> = &'a str;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<&'a str> {
+        parse(input)
+    }
+
+    fn part1(&self, signal: &&str) -> Result<usize> {
+        part1(signal)
+    }
+
+    fn part2(&self, signal: &&str) -> Result<usize> {
+        part2(signal)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day6_1.txt")), 7);
-        assert_eq!(part1(&read_input_from_file("sample/day6_2.txt")), 5);
-        assert_eq!(part1(&read_input_from_file("sample/day6_3.txt")), 6);
-        assert_eq!(part1(&read_input_from_file("sample/day6_4.txt")), 10);
-        assert_eq!(part1(&read_input_from_file("sample/day6_5.txt")), 11);
-        assert_eq!(part1(&read_input_from_file("input/day6.txt")), 1300);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day6, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day6_1.txt")), 19);
-        assert_eq!(part2(&read_input_from_file("sample/day6_2.txt")), 23);
-        assert_eq!(part2(&read_input_from_file("sample/day6_3.txt")), 23);
-        assert_eq!(part2(&read_input_from_file("sample/day6_4.txt")), 29);
-        assert_eq!(part2(&read_input_from_file("sample/day6_5.txt")), 26);
-        assert_eq!(part2(&read_input_from_file("input/day6.txt")), 3986);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day6, Part::Two)
     }
 }
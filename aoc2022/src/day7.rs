@@ -3,29 +3,38 @@ use std::{
     path::{Path, PathBuf},
 };
 
-fn create_file_table(lines: &[String]) -> HashMap<String, u32> {
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
+fn create_file_table(input: &str) -> Result<HashMap<String, u32>> {
     let mut file_table: HashMap<String, u32> = HashMap::new();
     let mut path = PathBuf::new();
     path.push("/");
 
-    for line in lines {
+    for (i, line) in input.lines().enumerate() {
         if line.starts_with("$ cd") {
-            if line[5..] == ".."[..] {
+            let dir = line
+                .strip_prefix("$ cd ")
+                .ok_or_else(|| Error::at_line(i + 1, format!("missing directory in \"{line}\"")))?;
+            if dir == ".." {
                 path.pop();
             } else {
-                path.push(&line[5..]);
+                path.push(dir);
             }
         } else if !line.starts_with("$ ls") && !line.starts_with("dir") {
-            let mut split_line = line.split(' ');
-            let file_size = str::parse::<u32>(split_line.next().unwrap()).unwrap();
-            let file_name = split_line.next().unwrap();
+            let (file_size, file_name) = line
+                .split_once(' ')
+                .and_then(|(size, name)| Some((size.parse::<u32>().ok()?, name)))
+                .ok_or_else(|| Error::at_line(i + 1, format!("invalid file entry \"{line}\"")))?;
 
             file_table
                 .entry(path.to_str().unwrap().to_string() + "/" + file_name)
                 .or_insert(file_size);
         }
     }
-    file_table
+    Ok(file_table)
 }
 
 fn create_dir_table(file_table: &HashMap<String, u32>) -> HashMap<String, u32> {
@@ -44,41 +53,77 @@ fn create_dir_table(file_table: &HashMap<String, u32>) -> HashMap<String, u32> {
     dir_table
 }
 
-pub fn part1(input: &[String]) -> u32 {
-    create_dir_table(&create_file_table(input))
+// Returns the total size of every directory, keyed by its path
+pub fn parse(input: &str) -> Result<HashMap<String, u32>> {
+    Ok(create_dir_table(&create_file_table(input)?))
+}
+
+pub fn part1(dir_table: &HashMap<String, u32>) -> Result<u32> {
+    Ok(dir_table
         .iter()
         .filter(|d| d.1 <= &100000u32)
         .map(|d| d.1)
-        .sum::<u32>()
+        .sum::<u32>())
 }
 
-pub fn part2(input: &[String]) -> u32 {
-    let dir_table = create_dir_table(&create_file_table(input));
-
-    let space_needed = 30000000 - (70000000 - dir_table.get("/").unwrap());
+pub fn part2(dir_table: &HashMap<String, u32>) -> Result<u32> {
+    let used_space = dir_table.get("/").copied().unwrap_or(0);
+    let free_space = 70000000u32
+        .checked_sub(used_space)
+        .ok_or_else(|| Error::new("the files do not fit on the disk"))?;
+    let space_needed = 30000000u32.saturating_sub(free_space);
 
-    *dir_table
-        .iter()
-        .filter(|d| d.1 >= &space_needed)
-        .map(|d| d.1)
+    dir_table
+        .values()
+        .filter(|size| **size >= space_needed)
         .min()
-        .unwrap()
+        .copied()
+        .ok_or_else(|| Error::new("no directory is large enough to delete"))
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 7;
+
+    type Input<'a> = HashMap<String, u32>;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(&self, input: &str) -> Result<HashMap<String, u32>> {
+        parse(input)
+    }
+
+    fn part1(&self, dir_table: &HashMap<String, u32>) -> Result<u32> {
+        part1(dir_table)
+    }
+
+    fn part2(&self, dir_table: &HashMap<String, u32>) -> Result<u32> {
+        part2(dir_table)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
+
+    #[test]
+    fn test_cd_without_directory() {
+        assert_eq!(
+            parse("$ cd /\n$ cd\n100 a").map(|_| ()),
+            Err(Error::at_line(2, "missing directory in \"$ cd\""))
+        );
+    }
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day7.txt")), 95437);
-        assert_eq!(part1(&read_input_from_file("input/day7.txt")), 1644735);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day7, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day7.txt")), 24933642);
-        assert_eq!(part2(&read_input_from_file("input/day7.txt")), 1300850);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day7, Part::Two)
     }
 }
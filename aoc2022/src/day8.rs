@@ -1,13 +1,31 @@
 use std::cmp::max;
 
-pub fn part1(input: &[String]) -> usize {
-    let mut grid: Vec<Vec<(i32, bool)>> = input
-        .iter()
-        .map(|line| {
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+};
+
+// Returns the height of each tree
+pub fn parse(input: &str) -> Result<Vec<Vec<i32>>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
             line.chars()
-                .map(|c| (i32::try_from(c.to_digit(10).unwrap()).unwrap(), false))
-                .collect::<Vec<(i32, bool)>>()
+                .map(|c| match c.to_digit(10) {
+                    Some(height) => Ok(i32::try_from(height).unwrap()),
+                    None => Err(Error::at_line(i + 1, format!("invalid tree height '{c}'"))),
+                })
+                .collect::<Result<Vec<i32>>>()
         })
+        .collect()
+}
+
+pub fn part1(heights: &[Vec<i32>]) -> Result<usize> {
+    // Each tree's height and whether it is visible
+    let mut grid: Vec<Vec<(i32, bool)>> = heights
+        .iter()
+        .map(|row| row.iter().map(|height| (*height, false)).collect())
         .collect();
 
     let mut visible_vertical: Vec<i32> = vec![-1; grid.len()];
@@ -40,79 +58,91 @@ pub fn part1(input: &[String]) -> usize {
         }
     }
 
-    grid.iter()
+    Ok(grid
+        .iter()
         .map(|row| row.iter().filter(|tree| tree.1).count())
-        .sum()
+        .sum())
 }
 
-fn get_scenic_score(grid: &mut Vec<Vec<(i32, bool)>>, x: usize, y: usize) -> u32 {
-    let tree_height: i32 = grid[y][x].0;
+fn get_scenic_score(grid: &[Vec<i32>], x: usize, y: usize) -> u32 {
+    let tree_height: i32 = grid[y][x];
     let mut score_up: u32 = 0;
     for i in (0..y).rev() {
         score_up += 1;
-        if grid[i][x].0 >= tree_height {
+        if grid[i][x] >= tree_height {
             break;
         }
     }
     let mut score_down: u32 = 0;
-    for i in (y + 1)..grid.len() {
+    for row in grid.iter().skip(y + 1) {
         score_down += 1;
-        if grid[i][x].0 >= tree_height {
+        if row[x] >= tree_height {
             break;
         }
     }
     let mut score_left: u32 = 0;
     for i in (0..x).rev() {
         score_left += 1;
-        if grid[y][i].0 >= tree_height {
+        if grid[y][i] >= tree_height {
             break;
         }
     }
     let mut score_right: u32 = 0;
-    for i in (x + 1)..grid.len() {
+    for tree in grid[y].iter().skip(x + 1) {
         score_right += 1;
-        if grid[y][i].0 >= tree_height {
+        if *tree >= tree_height {
             break;
         }
     }
     score_up * score_down * score_left * score_right
 }
 
-pub fn part2(input: &[String]) -> u32 {
-    let mut grid: Vec<Vec<(i32, bool)>> = input
-        .iter()
-        .map(|line| {
-            line.chars()
-                .map(|c| (i32::try_from(c.to_digit(10).unwrap()).unwrap(), false))
-                .collect::<Vec<(i32, bool)>>()
-        })
-        .collect();
-
+pub fn part2(heights: &[Vec<i32>]) -> Result<u32> {
     let mut max_score = 0;
-    for y in 0..grid.len() {
-        for x in 0..grid.len() {
-            max_score = max(max_score, get_scenic_score(&mut grid, x, y));
+    for y in 0..heights.len() {
+        for x in 0..heights.len() {
+            max_score = max(max_score, get_scenic_score(heights, x, y));
         }
     }
-    max_score
+    Ok(max_score)
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 8;
+
+    type Input<'a> = Vec<Vec<i32>>;
+    type Output1 = usize;
+    type Output2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<i32>>> {
+        parse(input)
+    }
+
+    fn part1(&self, heights: &Vec<Vec<i32>>) -> Result<usize> {
+        part1(heights)
+    }
+
+    fn part2(&self, heights: &Vec<Vec<i32>>) -> Result<u32> {
+        part2(heights)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{
-        day8::{part1, part2},
-        util::read_input_from_file,
-    };
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
+
+    use crate::day8::Day8;
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day8.txt")), 21);
-        assert_eq!(part1(&read_input_from_file("input/day8.txt")), 1672);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day8, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day8.txt")), 8);
-        assert_eq!(part2(&read_input_from_file("input/day8.txt")), 327180);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day8, Part::Two)
     }
 }
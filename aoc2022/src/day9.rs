@@ -1,10 +1,13 @@
-use crate::util::Point;
+use aoc_core::{
+    error::{Error, Result},
+    solution::Solution,
+    util::{Grid, Point},
+    visualize::{NoObserver, Observer, Palette},
+};
 use std::{cmp, collections::HashSet, vec};
 
-const DEBUG_PRINT: bool = false;
-
 #[derive(Debug)]
-enum Direction {
+pub enum Direction {
     Up,
     Down,
     Left,
@@ -24,10 +27,10 @@ impl Direction {
 
     fn get_move(&self) -> Point<i32> {
         match self {
-            Direction::Up => Point { x: 0, y: 1 },
-            Direction::Down => Point { x: 0, y: -1 },
-            Direction::Left => Point { x: -1, y: 0 },
-            Direction::Right => Point { x: 1, y: 0 },
+            Direction::Up => Point::new(0, 1),
+            Direction::Down => Point::new(0, -1),
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
         }
     }
 }
@@ -39,7 +42,7 @@ struct Rope {
 impl Rope {
     fn new(length: usize) -> Rope {
         Rope {
-            knots: vec![Point { x: 0, y: 0 }; length],
+            knots: vec![Point::new(0, 0); length],
         }
     }
 
@@ -49,9 +52,8 @@ impl Rope {
         let mut preceding_knot = *head;
 
         for knot in self.knots[1..].iter_mut() {
-            if knot.max_distance_to(&preceding_knot) > 1 {
-                knot.y += (preceding_knot.y - knot.y).signum();
-                knot.x += (preceding_knot.x - knot.x).signum();
+            if knot.chebyshev_distance_to(&preceding_knot) > 1 {
+                *knot += (preceding_knot - *knot).map(i32::signum);
             }
             preceding_knot = *knot;
         }
@@ -61,85 +63,133 @@ impl Rope {
         self.knots.last().unwrap()
     }
 
-    fn print(&self) {
-        let mut upper_right = Point { x: 0, y: 0 };
-        let mut lower_left = Point { x: 0, y: 0 };
+    fn draw(&self) -> Grid<char> {
+        let mut upper_right = Point::new(0, 0);
+        let mut lower_left = Point::new(0, 0);
 
         for knot in self.knots.iter() {
-            upper_right.x = cmp::max(upper_right.x, knot.x);
-            upper_right.y = cmp::max(upper_right.y, knot.y);
-            lower_left.x = cmp::min(lower_left.x, knot.x);
-            lower_left.y = cmp::min(lower_left.y, knot.y);
+            for axis in 0..2 {
+                upper_right[axis] = cmp::max(upper_right[axis], knot[axis]);
+                lower_left[axis] = cmp::min(lower_left[axis], knot[axis]);
+            }
         }
 
-        let width: usize = (upper_right.x - lower_left.x + 1).try_into().unwrap();
-        let height: usize = (upper_right.y - lower_left.y + 1).try_into().unwrap();
-
-        let mut grid = vec![vec!['.'; width]; height];
+        let width = (upper_right.x() - lower_left.x() + 1) as usize;
+        let height = (upper_right.y() - lower_left.y() + 1) as usize;
+        let mut grid = Grid::new(width, height, '.');
 
+        // The y axis points up, so the rows are flipped
+        let to_grid = |p: Point<i32>| Point::new(p.x() - lower_left.x(), upper_right.y() - p.y());
+        grid[to_grid(Point::new(0, 0))] = 's';
         for (i, knot) in self.knots.iter().enumerate().rev() {
-            grid[(knot.y - lower_left.y) as usize][(knot.x - lower_left.x) as usize] =
-                char::from_digit(i.try_into().unwrap(), 10).unwrap();
-        }
-
-        grid[(0 - lower_left.y) as usize][(0 - lower_left.x) as usize] = 's';
-
-        println!("------------------------------");
-        for row in grid.iter().rev() {
-            for c in row {
-                print!("{}", c);
-            }
-            println!();
+            grid[to_grid(*knot)] = char::from_digit(i as u32 % 10, 10).unwrap();
         }
-        println!("------------------------------");
+        grid
     }
 }
 
-fn simulate_rope(input: &[String], rope_length: usize) -> usize {
+// Returns the direction and the number of steps of each motion
+pub fn parse(input: &str) -> Result<Vec<(Direction, u32)>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.split_once(' ')
+                .and_then(|(dir, num_steps)| {
+                    Some((Direction::from_str(dir)?, num_steps.parse::<u32>().ok()?))
+                })
+                .ok_or_else(|| Error::at_line(i + 1, format!("invalid motion \"{line}\"")))
+        })
+        .collect()
+}
+
+fn simulate_rope(
+    motions: &[(Direction, u32)],
+    rope_length: usize,
+    observer: &mut dyn Observer,
+) -> Result<usize> {
     let mut rope = Rope::new(rope_length);
     let mut visited_points: HashSet<Point<i32>> = HashSet::new();
     visited_points.insert(*rope.get_tail());
 
-    input.iter().for_each(|line| {
-        let mut split_line = line.split_whitespace();
-
-        let dir = Direction::from_str(split_line.next().unwrap()).unwrap();
-        let num_steps = str::parse::<u32>(split_line.next().unwrap()).unwrap();
-
-        for _ in 0..num_steps {
-            rope.move_head(&dir);
-            if DEBUG_PRINT {
-                rope.print();
-            }
+    for (dir, num_steps) in motions {
+        for _ in 0..*num_steps {
+            rope.move_head(dir);
+            observer.show(|| rope.draw());
             visited_points.insert(*rope.get_tail());
         }
-    });
-    visited_points.len()
+    }
+    Ok(visited_points.len())
+}
+
+pub fn part1(motions: &[(Direction, u32)], observer: &mut dyn Observer) -> Result<usize> {
+    simulate_rope(motions, 2, observer)
 }
 
-pub fn part1(input: &[String]) -> usize {
-    simulate_rope(input, 2)
+pub fn part2(motions: &[(Direction, u32)], observer: &mut dyn Observer) -> Result<usize> {
+    simulate_rope(motions, 10, observer)
 }
 
-pub fn part2(input: &[String]) -> usize {
-    simulate_rope(input, 10)
+pub struct Day9;
+
+impl Solution for Day9 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 9;
+
+    type Input<'a> = Vec<(Direction, u32)>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const PALETTE: Palette = Palette {
+        background: [20, 20, 20],
+        colors: &[
+            ('.', [20, 20, 20]),
+            ('s', [60, 180, 75]),
+            ('0', [230, 25, 75]),
+        ],
+    };
+
+    fn parse(&self, input: &str) -> Result<Vec<(Direction, u32)>> {
+        parse(input)
+    }
+
+    fn part1(&self, motions: &Vec<(Direction, u32)>) -> Result<usize> {
+        part1(motions, &mut NoObserver)
+    }
+
+    fn part2(&self, motions: &Vec<(Direction, u32)>) -> Result<usize> {
+        part2(motions, &mut NoObserver)
+    }
+
+    fn visualize_part1(
+        &self,
+        motions: &Vec<(Direction, u32)>,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<usize>> {
+        Some(part1(motions, observer))
+    }
+
+    fn visualize_part2(
+        &self,
+        motions: &Vec<(Direction, u32)>,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<usize>> {
+        Some(part2(motions, observer))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::read_input_from_file;
+    use aoc_core::{answers::check_answers, error::Result, solution::Part};
 
     #[test]
-    fn test_part1() {
-        assert_eq!(part1(&read_input_from_file("sample/day9_1.txt")), 13);
-        assert_eq!(part1(&read_input_from_file("input/day9.txt")), 6311);
+    fn test_part1() -> Result<()> {
+        check_answers(&Day9, Part::One)
     }
 
     #[test]
-    fn test_part2() {
-        assert_eq!(part2(&read_input_from_file("sample/day9_1.txt")), 1);
-        assert_eq!(part2(&read_input_from_file("sample/day9_2.txt")), 36);
-        assert_eq!(part2(&read_input_from_file("input/day9.txt")), 2482);
+    fn test_part2() -> Result<()> {
+        check_answers(&Day9, Part::Two)
     }
 }
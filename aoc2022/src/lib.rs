@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use aoc_core::solution::Registry;
+
+// The helpers the days are built on, so that users of the crate don't need to
+// depend on aoc-core for them
+pub use aoc_core::{error, util};
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+pub const YEAR: u16 = 2022;
+
+pub fn register(registry: &mut Registry) {
+    registry
+        .add_year(YEAR, Path::new(env!("CARGO_MANIFEST_DIR")).join("res"))
+        .register(day1::Day1)
+        .register(day2::Day2)
+        .register(day3::Day3)
+        .register(day4::Day4)
+        .register(day5::Day5)
+        .register(day6::Day6)
+        .register(day7::Day7)
+        .register(day8::Day8)
+        .register(day9::Day9)
+        .register(day10::Day10)
+        .register(day11::Day11)
+        .register(day12::Day12)
+        .register(day13::Day13)
+        .register(day14::Day14)
+        .register(day15::Day15)
+        .register(day16::Day16)
+        .register(day17::Day17)
+        .register(day18::Day18)
+        .register(day19::Day19)
+        .register(day20::Day20)
+        .register(day21::Day21)
+        .register(day22::Day22)
+        .register(day23::Day23)
+        .register(day24::Day24)
+        .register(day25::Day25);
+}